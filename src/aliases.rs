@@ -0,0 +1,180 @@
+//! Contains logic around names of packages that differ across distributions and package managers.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::environment::get_config_path;
+use crate::platforms::Distribution;
+
+/// Aliases bundled with foraget.
+const BUNDLED_ALIASES: &str = include_str!("../data/aliases.toml");
+
+/// The file in the configuration directory with aliases of the user.
+pub const USER_ALIASES_FILE: &str = "aliases.toml";
+
+/// Names of packages across distributions and package managers, keyed by their canonical names.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(transparent)]
+pub struct Aliases {
+    names: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Aliases {
+    /// Gets the name of a package for a package manager on the supplied distribution.
+    ///
+    /// Returns the supplied name when there is no alias for it.
+    pub fn get_name<'a>(
+        &'a self,
+        name: &'a str,
+        distribution: &Option<Distribution>,
+        package_manager: &str,
+    ) -> &'a str {
+        self.names
+            .get(name)
+            .and_then(|n| get_override(n, distribution, package_manager))
+            .unwrap_or(name)
+    }
+
+    /// Gets the canonical name of a package from its name for a package manager on the supplied
+    /// distribution.
+    ///
+    /// Returns the supplied name when no alias resolves to it.
+    pub fn get_canonical_name<'a>(
+        &'a self,
+        name: &'a str,
+        distribution: &Option<Distribution>,
+        package_manager: &str,
+    ) -> &'a str {
+        self.names
+            .keys()
+            .find(|c| c.as_str() != name && self.get_name(c, distribution, package_manager) == name)
+            .map_or(name, |c| c.as_str())
+    }
+
+    /// Adds the supplied aliases, replacing existing names for the same keys.
+    pub fn merge(mut self, other: Aliases) -> Aliases {
+        for (canonical_name, names) in other.names {
+            self.names.entry(canonical_name).or_default().extend(names);
+        }
+
+        self
+    }
+}
+
+/// Gets the name overridden for a package manager or the supplied distribution.
+///
+/// An override for the package manager wins over one for the distribution, which in turn wins
+/// over one for a distribution it is based on.
+pub fn get_override<'a>(
+    names: &'a BTreeMap<String, String>,
+    distribution: &Option<Distribution>,
+    package_manager: &str,
+) -> Option<&'a str> {
+    let ids = match distribution {
+        Some(d) => std::iter::once(&d.id).chain(d.id_like.iter()).collect(),
+        None => vec![],
+    };
+
+    std::iter::once(package_manager)
+        .chain(ids.into_iter().map(|i| i.as_str()))
+        .find_map(|k| names.get(k))
+        .map(|n| n.as_str())
+}
+
+/// Parses aliases from their TOML text.
+pub fn parse_aliases(text: &str) -> Result<Aliases, String> {
+    toml::from_str(text).map_err(|e| e.to_string())
+}
+
+/// Gets the aliases bundled with foraget.
+pub fn get_bundled_aliases() -> Aliases {
+    parse_aliases(BUNDLED_ALIASES).expect("The bundled aliases are invalid!")
+}
+
+/// Reads the aliases of the user from the configuration directory.
+///
+/// Returns no aliases when the file does not exist.
+pub fn read_user_aliases() -> Result<Aliases, String> {
+    match get_config_path(USER_ALIASES_FILE) {
+        Some(path) if path.is_file() => parse_aliases(
+            &fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?,
+        )
+        .map_err(|e| format!("{}: {}", path.display(), e)),
+        _ => Ok(Aliases::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platforms::parse_os_release;
+
+    #[test]
+    fn get_bundled_aliases_valid() {
+        assert!(
+            parse_aliases(BUNDLED_ALIASES).is_ok(),
+            "Parses the bundled aliases"
+        );
+    }
+
+    #[test]
+    fn get_name_for_distribution() {
+        let ubuntu = parse_os_release(include_str!("../tests/fixtures/os-release/ubuntu"));
+
+        assert_eq!(
+            "fd-find",
+            get_bundled_aliases().get_name("fd", &ubuntu, "apt"),
+            "Gets the name for a distribution the system is based on"
+        );
+    }
+
+    #[test]
+    fn get_name_for_package_manager() {
+        assert_eq!(
+            "node",
+            get_bundled_aliases().get_name("nodejs", &None, "brew"),
+            "Gets the name for a package manager"
+        );
+    }
+
+    #[test]
+    fn get_name_without_alias() {
+        let arch = parse_os_release(include_str!("../tests/fixtures/os-release/arch"));
+
+        assert_eq!(
+            "emacs",
+            get_bundled_aliases().get_name("emacs", &arch, "pacman"),
+            "Gets the supplied name without an alias"
+        );
+    }
+
+    #[test]
+    fn get_canonical_name_some_values() {
+        let rocky = parse_os_release(include_str!("../tests/fixtures/os-release/rocky"));
+
+        assert_eq!(
+            "vim",
+            get_bundled_aliases().get_canonical_name("vim-enhanced", &rocky, "dnf"),
+            "Gets the canonical name from the name for a distribution"
+        );
+    }
+
+    #[test]
+    fn merge_user_aliases() {
+        let aliases = get_bundled_aliases()
+            .merge(parse_aliases("[fd]\ndebian = \"fdfind\"\n\n[bat]\nbrew = \"bat\"").unwrap());
+        let ubuntu = parse_os_release(include_str!("../tests/fixtures/os-release/ubuntu"));
+
+        assert_eq!(
+            "fdfind",
+            aliases.get_name("fd", &ubuntu, "apt"),
+            "Replaces a bundled name with the one from the user"
+        );
+        assert_eq!(
+            "p7zip-full",
+            aliases.get_name("p7zip", &ubuntu, "apt"),
+            "Keeps bundled names the user does not replace"
+        );
+    }
+}
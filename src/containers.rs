@@ -0,0 +1,203 @@
+//! Contains logic around running package managers inside containers.
+
+use crate::environment::{does_exist, quote, run_command_and_get_list};
+use crate::package_managers::{get_known_package_managers_for_linux, PackageManager};
+use crate::platforms::{filter_for_distribution, parse_os_release, Distribution};
+
+/// An enumeration of supported container tools.
+#[derive(Debug, PartialEq)]
+pub enum ContainerTool {
+    Distrobox,
+    Toolbox,
+}
+
+/// A container that package managers can be run inside.
+#[derive(Debug, PartialEq)]
+pub struct Container {
+    pub name: String,
+    pub tool: ContainerTool,
+}
+
+impl Container {
+    /// Generates a command that runs the supplied command inside the container.
+    pub fn gen_command(&self, command: &str) -> String {
+        match self.tool {
+            ContainerTool::Distrobox => format!(
+                "distrobox enter {} -- sh -c {}",
+                quote(&self.name),
+                quote(command)
+            ),
+            ContainerTool::Toolbox => format!(
+                "toolbox run --container {} sh -c {}",
+                quote(&self.name),
+                quote(command)
+            ),
+        }
+    }
+
+    /// Generates a command that exports an application or binary installed inside the container
+    /// to the host.
+    ///
+    /// Returns `None` for containers that cannot export to the host.
+    pub fn gen_export_command(&self, package: &str) -> Option<String> {
+        match self.tool {
+            ContainerTool::Distrobox => Some(self.gen_command(&format!(
+                "distrobox-export --app {} || distrobox-export --bin \"$(command -v {})\"",
+                quote(package),
+                quote(package)
+            ))),
+            ContainerTool::Toolbox => None,
+        }
+    }
+
+    /// Returns whether a command exists inside the container.
+    pub fn does_exist(&self, command: &str) -> bool {
+        !run_command_and_get_list(&self.gen_command(&format!("command -v {}", command)))[0]
+            .is_empty()
+    }
+
+    /// Returns a label for a package manager running inside the container.
+    pub fn get_label(&self, package_manager: &PackageManager) -> String {
+        format!("{}@{}", package_manager.command_name, self.name)
+    }
+}
+
+/// Looks up a distrobox or toolbox container by its name.
+pub fn get_container(name: &str) -> Option<Container> {
+    if does_exist("distrobox")
+        && get_distrobox_names(&run_command_and_get_list("distrobox list --no-color"))
+            .contains(&name.to_string())
+    {
+        Some(Container {
+            name: name.to_string(),
+            tool: ContainerTool::Distrobox,
+        })
+    } else if does_exist("toolbox")
+        && get_toolbox_names(&run_command_and_get_list("toolbox list --containers"))
+            .contains(&name.to_string())
+    {
+        Some(Container {
+            name: name.to_string(),
+            tool: ContainerTool::Toolbox,
+        })
+    } else {
+        None
+    }
+}
+
+/// Extracts container names from the output of `distrobox list`.
+fn get_distrobox_names(lines: &[String]) -> Vec<String> {
+    lines
+        .iter()
+        .skip(1)
+        .filter_map(|l| l.split('|').nth(1))
+        .map(|n| n.trim().to_string())
+        .collect()
+}
+
+/// Extracts container names from the output of `toolbox list --containers`.
+fn get_toolbox_names(lines: &[String]) -> Vec<String> {
+    lines
+        .iter()
+        .skip(1)
+        .filter_map(|l| l.split_whitespace().nth(1))
+        .map(|n| n.to_string())
+        .collect()
+}
+
+/// Gets the distribution inside a container.
+pub fn get_container_distribution(container: &Container) -> Option<Distribution> {
+    let os_release = run_command_and_get_list(&container.gen_command("cat /etc/os-release"));
+
+    parse_os_release(&os_release.join("\n"))
+}
+
+/// Gets the package managers relevant to the distribution inside a container.
+pub fn get_container_package_managers(distribution: &Option<Distribution>) -> Vec<PackageManager> {
+    filter_for_distribution(get_known_package_managers_for_linux(), distribution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_distrobox() -> Container {
+        Container {
+            name: String::from("arch"),
+            tool: ContainerTool::Distrobox,
+        }
+    }
+
+    #[test]
+    fn gen_command_distrobox() {
+        assert_eq!(
+            String::from("distrobox enter 'arch' -- sh -c 'sudo pacman -S emacs'"),
+            get_distrobox().gen_command("sudo pacman -S emacs"),
+            "Generates a command to run inside a distrobox container"
+        );
+    }
+
+    #[test]
+    fn gen_command_toolbox() {
+        assert_eq!(
+            String::from("toolbox run --container 'fedora' sh -c 'dnf search emacs'"),
+            Container {
+                name: String::from("fedora"),
+                tool: ContainerTool::Toolbox,
+            }
+            .gen_command("dnf search emacs"),
+            "Generates a command to run inside a toolbox container"
+        );
+    }
+
+    #[test]
+    fn gen_export_command_distrobox() {
+        assert_eq!(
+            Some(String::from(
+                "distrobox enter 'arch' -- sh -c 'distrobox-export --app '\\''emacs'\\'' || distrobox-export --bin \"$(command -v '\\''emacs'\\'')\"'"
+            )),
+            get_distrobox().gen_export_command("emacs"),
+            "Generates a command to export an application or binary, quoting the package"
+        );
+    }
+
+    #[test]
+    fn gen_export_command_toolbox() {
+        assert_eq!(
+            None,
+            Container {
+                name: String::from("fedora"),
+                tool: ContainerTool::Toolbox,
+            }
+            .gen_export_command("emacs"),
+            "Does not export from a toolbox container"
+        );
+    }
+
+    #[test]
+    fn get_distrobox_names_some_values() {
+        assert_eq!(
+            vec![String::from("arch"), String::from("ubuntu")],
+            get_distrobox_names(&[
+                String::from("ID           | NAME    | STATUS      | IMAGE"),
+                String::from("a1b2c3d4e5f6 | arch    | Up 2 hours  | quay.io/toolbx/arch-toolbox"),
+                String::from(
+                    "f6e5d4c3b2a1 | ubuntu  | Exited      | quay.io/toolbx/ubuntu-toolbox"
+                ),
+            ]),
+            "Extracts container names from distrobox output"
+        );
+    }
+
+    #[test]
+    fn get_toolbox_names_some_values() {
+        assert_eq!(
+            vec![String::from("fedora-toolbox-39")],
+            get_toolbox_names(&[
+                String::from("CONTAINER ID  CONTAINER NAME     CREATED      STATUS   IMAGE NAME"),
+                String::from("0a1b2c3d4e5f  fedora-toolbox-39  2 weeks ago  running  registry.fedoraproject.org/fedora-toolbox:39"),
+            ]),
+            "Extracts container names from toolbox output"
+        );
+    }
+}
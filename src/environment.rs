@@ -1,6 +1,21 @@
 //! Contains functions to interact with the environment.
 
+// The helpers here predate these lints
+#![allow(
+    clippy::comparison_to_empty,
+    clippy::io_other_error,
+    clippy::len_zero,
+    clippy::lines_filter_map_ok,
+    clippy::needless_borrow,
+    clippy::needless_range_loop,
+    clippy::ptr_arg,
+    clippy::unnecessary_to_owned,
+    dead_code
+)]
+
+use std::env;
 use std::io::{BufRead, BufReader, Error, ErrorKind};
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
 /// Runs a shell command with no stdin and returns the output as a list.
@@ -82,19 +97,42 @@ pub fn run_command_and_print_result(command: &str) {
 
 /// Runs a shell command with full stdio only returns the status.
 ///
+/// Returns an error when the command exits unsuccessfully.
+///
 /// # Example
 ///
 /// ```
 /// run_command_continuous("pacman -S emacs");
 /// ```
 pub fn run_command_continuous(command: &str) -> Result<(), Error> {
+    match run_command_and_get_status(command)? {
+        0 => Ok(()),
+        code => Err(Error::new(
+            ErrorKind::Other,
+            format!("The command exited with {}.", code),
+        )),
+    }
+}
+
+/// Runs a shell command with full stdio and returns its exit code.
+///
+/// Returns `-1` when the command is terminated by a signal.
+///
+/// # Example
+///
+/// ```
+/// run_command_and_get_status("pacman -S emacs");
+/// ```
+pub fn run_command_and_get_status(command: &str) -> Result<i32, Error> {
     // Run the command and capture the stdout
-    let stdout = Command::new("/bin/bash")
+    let mut child = Command::new("/bin/bash")
         .arg("-c")
         .arg(command)
         .stdout(Stdio::piped())
-        .spawn()?
+        .spawn()?;
+    let stdout = child
         .stdout
+        .take()
         .ok_or_else(|| Error::new(ErrorKind::Other, "Could not capture standard output."))?;
 
     // Print the stdout
@@ -104,8 +142,74 @@ pub fn run_command_continuous(command: &str) -> Result<(), Error> {
         .filter_map(|line| line.ok())
         .for_each(|line| println!("{}", line));
 
-    // Exit the function
-    Ok(())
+    // Return the exit code of the command
+    Ok(child.wait()?.code().unwrap_or(-1))
+}
+
+/// Runs a shell command attached to the terminal and returns its exit code.
+///
+/// Returns `-1` when the command is terminated by a signal.
+///
+/// # Example
+///
+/// ```
+/// run_command_interactive("flatpak run org.gnu.emacs");
+/// ```
+pub fn run_command_interactive(command: &str) -> Result<i32, Error> {
+    Ok(Command::new("/bin/bash")
+        .arg("-c")
+        .arg(command)
+        .status()?
+        .code()
+        .unwrap_or(-1))
+}
+
+/// Quotes a string so that a shell reads it as a single argument.
+///
+/// # Example
+///
+/// ```
+/// quote("it's");
+/// ```
+pub fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Gets the path to a file in the configuration directory of foraget.
+///
+/// Follows `XDG_CONFIG_HOME`, falling back to `~/.config`. Returns `None` when neither is known.
+///
+/// # Example
+///
+/// ```
+/// get_config_path("aliases.toml");
+/// ```
+pub fn get_config_path(file: &str) -> Option<PathBuf> {
+    get_directory_path("XDG_CONFIG_HOME", ".config", file)
+}
+
+/// Gets the path to a file in the state directory of foraget.
+///
+/// Follows `XDG_STATE_HOME`, falling back to `~/.local/state`. Returns `None` when neither is
+/// known.
+///
+/// # Example
+///
+/// ```
+/// get_state_path("journal.toml");
+/// ```
+pub fn get_state_path(file: &str) -> Option<PathBuf> {
+    get_directory_path("XDG_STATE_HOME", ".local/state", file)
+}
+
+/// Gets the path to a file in the directory of foraget under an XDG base directory, or its
+/// fallback under the home directory.
+fn get_directory_path(variable: &str, fallback: &str, file: &str) -> Option<PathBuf> {
+    env::var_os(variable)
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(fallback)))
+        .map(|d| d.join("foraget").join(file))
 }
 
 /// Prints a list of strings to stdout.
@@ -156,10 +260,46 @@ pub fn prompt_for_value_from_list(options: &Vec<String>) -> String {
     result.unwrap_or(vec![String::from("")])[0].to_string()
 }
 
+/// Prompt to choose any number of options from the supplied list
+///
+/// # Example
+///
+/// ```
+/// prompt_for_values_from_list(vec!["one", "two", "three"]);
+/// ```
+pub fn prompt_for_values_from_list(options: &Vec<String>) -> Vec<String> {
+    run_command_and_get_result(&format!(
+        "echo -e \"{}\" | fzf --multi",
+        get_multiline_string(options)
+    ))
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|v| !v.is_empty())
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn quote_simple() {
+        assert_eq!(
+            "'pacman -S emacs'",
+            quote("pacman -S emacs"),
+            "Quotes a string with spaces"
+        );
+    }
+
+    #[test]
+    fn quote_with_single_quotes() {
+        assert_eq!(
+            "'echo '\\''hi'\\'''",
+            quote("echo 'hi'"),
+            "Escapes single quotes within a quoted string"
+        );
+    }
+
     #[test]
     fn get_multiline_string_some_values() {
         assert_eq!(
@@ -0,0 +1,61 @@
+//! Contains shell hooks that suggest packages for commands that are not found.
+
+/// The hook for bash, through `command_not_found_handle`.
+const BASH_HOOK: &str = r#"command_not_found_handle() {
+    printf '%s: command not found\n' "$1" >&2
+    foraget provides "$1" >&2
+    return 127
+}
+"#;
+
+/// The hook for zsh, through `command_not_found_handler`.
+const ZSH_HOOK: &str = r#"command_not_found_handler() {
+    printf '%s: command not found\n' "$1" >&2
+    foraget provides "$1" >&2
+    return 127
+}
+"#;
+
+/// The hook for fish, through `fish_command_not_found`.
+const FISH_HOOK: &str = r#"function fish_command_not_found
+    printf '%s: command not found\n' $argv[1] >&2
+    foraget provides $argv[1] >&2
+end
+"#;
+
+/// Shells that hooks are available for.
+pub const SHELLS: [&str; 3] = ["bash", "zsh", "fish"];
+
+/// Gets the hook for a shell.
+///
+/// Returns `None` for shells that there is no hook for.
+pub fn get_hook(shell: &str) -> Option<&'static str> {
+    match shell {
+        "bash" => Some(BASH_HOOK),
+        "zsh" => Some(ZSH_HOOK),
+        "fish" => Some(FISH_HOOK),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_hook_available() {
+        assert!(
+            SHELLS.iter().all(|s| get_hook(s).is_some()),
+            "Gets a hook for every supported shell"
+        );
+    }
+
+    #[test]
+    fn get_hook_unavailable() {
+        assert_eq!(
+            None,
+            get_hook("tcsh"),
+            "Gets no hook for an unsupported shell"
+        );
+    }
+}
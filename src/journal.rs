@@ -0,0 +1,275 @@
+//! Contains logic around the journal of operations carried out through foraget.
+
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::environment::{get_state_path, run_command_and_get_list};
+
+/// The file in the state directory with the journal.
+pub const JOURNAL_FILE: &str = "journal.toml";
+
+/// An enumeration of operations that are recorded in the journal.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Install,
+    Uninstall,
+    Upgrade,
+}
+
+impl Operation {
+    /// Returns the name of the operation.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Operation::Install => "install",
+            Operation::Uninstall => "uninstall",
+            Operation::Upgrade => "upgrade",
+        }
+    }
+}
+
+/// An operation recorded in the journal.
+///
+/// The package is empty for operations on every package, like upgrading the whole system. The
+/// transaction is the one the package manager recorded the operation as, when it keeps them, and
+/// an operation that undoes another one refers to it by its ID.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Entry {
+    pub id: u64,
+    pub timestamp: u64,
+    pub user: String,
+    pub operation: Operation,
+    pub backend: String,
+    pub package: String,
+    pub version_before: String,
+    pub version_after: String,
+    pub command: String,
+    pub status: i32,
+    #[serde(default)]
+    pub transaction: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverses: Option<u64>,
+}
+
+impl Entry {
+    /// Creates an entry for an operation carried out now by the current user.
+    ///
+    /// The ID is assigned when the entry is added to the journal.
+    pub fn new(operation: Operation, backend: &str, package: &str, command: &str) -> Entry {
+        Entry {
+            id: 0,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            user: get_user(),
+            operation,
+            backend: backend.to_string(),
+            package: package.to_string(),
+            version_before: String::new(),
+            version_after: String::new(),
+            command: command.to_string(),
+            status: 0,
+            transaction: String::new(),
+            reverses: None,
+        }
+    }
+
+    /// Returns whether the entry matches all of the supplied filters.
+    pub fn matches(
+        &self,
+        package: Option<&str>,
+        backend: Option<&str>,
+        operation: Option<Operation>,
+    ) -> bool {
+        package.map_or(true, |p| p == self.package)
+            && backend.map_or(true, |b| b == self.backend)
+            && operation.map_or(true, |o| o == self.operation)
+    }
+}
+
+/// The journal, as it is stored on disk.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Journal {
+    #[serde(default)]
+    entries: Vec<Entry>,
+}
+
+/// Gets the user that foraget runs on behalf of, looking past `sudo`.
+fn get_user() -> String {
+    env::var("SUDO_USER")
+        .or_else(|_| env::var("USER"))
+        .unwrap_or_else(|_| run_command_and_get_list("id -un")[0].to_string())
+}
+
+/// Parses entries from the TOML text of the journal.
+pub fn parse_entries(text: &str) -> Result<Vec<Entry>, String> {
+    toml::from_str::<Journal>(text)
+        .map(|j| j.entries)
+        .map_err(|e| e.to_string())
+}
+
+/// Generates the TOML text of an entry, to be appended to the journal.
+pub fn gen_entry_text(entry: &Entry) -> Result<String, String> {
+    toml::to_string(&Journal {
+        entries: vec![entry.clone()],
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Reads the entries from the journal, oldest first.
+///
+/// Returns no entries when the journal does not exist yet.
+pub fn read_entries() -> Result<Vec<Entry>, String> {
+    match get_state_path(JOURNAL_FILE) {
+        Some(path) if path.is_file() => parse_entries(
+            &fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?,
+        )
+        .map_err(|e| format!("{}: {}", path.display(), e)),
+        _ => Ok(vec![]),
+    }
+}
+
+/// Adds an entry to the journal, with the ID next to that of the last entry.
+pub fn add_entry(mut entry: Entry) -> Result<u64, String> {
+    let path = get_state_path(JOURNAL_FILE)
+        .ok_or_else(|| String::from("The state directory could not be found."))?;
+
+    entry.id = read_entries()?.last().map_or(1, |e| e.id + 1);
+
+    let text = format!("\n{}", gen_entry_text(&entry)?);
+
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut f| f.write_all(text.as_bytes()))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    Ok(entry.id)
+}
+
+/// Formats a UNIX timestamp as a date and time in UTC.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+
+    // Convert days since the epoch to a civil date
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_entry() -> Entry {
+        Entry {
+            id: 7,
+            timestamp: 1_700_000_000,
+            user: String::from("someone"),
+            operation: Operation::Install,
+            backend: String::from("pacman"),
+            package: String::from("emacs"),
+            version_before: String::new(),
+            version_after: String::from("29.1-2"),
+            command: String::from("sudo pacman -S emacs --noconfirm"),
+            status: 0,
+            transaction: String::new(),
+            reverses: None,
+        }
+    }
+
+    #[test]
+    fn gen_entry_text_round_trip() {
+        let text = format!(
+            "{}\n{}",
+            gen_entry_text(&get_entry()).unwrap(),
+            gen_entry_text(&get_entry()).unwrap()
+        );
+
+        assert_eq!(
+            Ok(vec![get_entry(), get_entry()]),
+            parse_entries(&text),
+            "Parses back entries appended one after another"
+        );
+    }
+
+    #[test]
+    fn gen_entry_text_reversal() {
+        let mut entry = get_entry();
+        entry.reverses = Some(3);
+
+        assert_eq!(
+            Ok(vec![entry.clone()]),
+            parse_entries(&gen_entry_text(&entry).unwrap()),
+            "Parses back the operation an entry reverses"
+        );
+    }
+
+    #[test]
+    fn parse_entries_without_transaction() {
+        let text = gen_entry_text(&get_entry())
+            .unwrap()
+            .replace("transaction = \"\"\n", "");
+
+        assert_eq!(
+            Ok(vec![get_entry()]),
+            parse_entries(&text),
+            "Parses entries recorded before transactions were"
+        );
+    }
+
+    #[test]
+    fn matches_filters() {
+        assert!(
+            get_entry().matches(Some("emacs"), None, Some(Operation::Install)),
+            "Matches an entry with the supplied package and operation"
+        );
+        assert!(
+            !get_entry().matches(None, Some("flatpak"), None),
+            "Does not match an entry with another package manager"
+        );
+    }
+
+    #[test]
+    fn format_timestamp_some_values() {
+        assert_eq!(
+            "2023-11-14 22:13",
+            format_timestamp(1_700_000_000),
+            "Formats a timestamp as a date and time"
+        );
+        assert_eq!(
+            "2024-02-29 00:00",
+            format_timestamp(1_709_164_800),
+            "Formats a timestamp on a leap day"
+        );
+    }
+}
@@ -2,19 +2,43 @@
 //!
 //! `foraget` is a simple universal package manager for Unix-like systems.
 
+use std::fs;
 use std::process;
 
 use ansi_term::Color;
-use clap::{crate_authors, crate_description, crate_name, crate_version, App, Arg, SubCommand};
+use clap::{
+    crate_authors, crate_description, crate_name, crate_version, App, AppSettings, Arg, ArgMatches,
+    SubCommand,
+};
 
+mod aliases;
+mod containers;
 mod environment;
+mod hooks;
+mod journal;
+mod manifests;
 mod package_managers;
 mod platforms;
+mod privileges;
+mod records;
+mod repositories;
 mod tasks;
+mod versions;
 
+use aliases::{get_bundled_aliases, read_user_aliases, Aliases};
+use containers::get_container;
 use environment::does_exist;
-use package_managers::PackageManager;
-use platforms::get_relevant_package_managers;
+use hooks::{get_hook, SHELLS};
+use journal::Operation;
+use manifests::{read_manifest, ExportFormat};
+use package_managers::{InstallReason, PackageManager};
+use platforms::{get_distribution, get_relevant_package_managers, Distribution};
+use privileges::get_privilege;
+use repositories::parse_repository;
+use tasks::Options;
+
+/// The exit code when `outdated` finds pending updates, so that scripts can check for them.
+const OUTDATED_EXIT_CODE: i32 = 100;
 
 /// The entry point to foraget.
 ///
@@ -25,10 +49,13 @@ fn main() {
     // Check for dependencies
     check_for_dependencies();
 
+    // Detect the distribution, if any
+    let distribution = get_distribution();
+
     // Get relevant package managers
-    if let Some(package_managers) = get_relevant_package_managers() {
+    if let Some(package_managers) = get_relevant_package_managers(&distribution) {
         // Run foraget for the relevant package managers
-        run(&package_managers);
+        run(&package_managers, &distribution);
     } else {
         // Print error message about non-implementation for the platform
         println!(
@@ -42,7 +69,7 @@ fn main() {
 }
 
 fn check_for_dependencies() {
-    let dependencies = vec![("fzf", "A command-line fuzzy finder")];
+    let dependencies = [("fzf", "A command-line fuzzy finder")];
 
     dependencies.iter().for_each(|d| {
         if !does_exist(d.0) {
@@ -54,11 +81,253 @@ fn check_for_dependencies() {
 }
 
 /// Runs foraget with the supplied package managers.
-fn run(package_managers: &Vec<PackageManager>) {
-    let matches = App::new(crate_name!())
+fn run(package_managers: &[PackageManager], distribution: &Option<Distribution>) {
+    let matches = get_app().get_matches();
+
+    if let Some(matches) = matches.subcommand_matches("init") {
+        // Init package sources
+        tasks::init(package_managers, distribution, &get_options(matches));
+    } else if let Some(matches) = matches.subcommand_matches("search") {
+        // Search for the package across relevant package managers
+        tasks::search(
+            package_managers,
+            distribution,
+            matches.value_of("PACKAGE").unwrap(),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("install") {
+        // Prompt to install the packages from the relevant package managers
+        tasks::install(
+            package_managers,
+            distribution,
+            &matches.values_of("PACKAGE").unwrap().collect::<Vec<&str>>(),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("uninstall") {
+        // Try uninstalling the packages using the relevant package managers
+        tasks::uninstall(
+            package_managers,
+            &matches.values_of("PACKAGE").unwrap().collect::<Vec<&str>>(),
+            matches.is_present("yes"),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("run") {
+        // Run the package through the package manager it was installed through
+        process::exit(tasks::run(
+            package_managers,
+            matches.value_of("PACKAGE").unwrap(),
+            &matches
+                .values_of("ARGS")
+                .map(|v| v.collect::<Vec<&str>>())
+                .unwrap_or_default(),
+            &get_options(matches),
+        ));
+    } else if let Some(matches) = matches.subcommand_matches("sync") {
+        // Install packages declared in the manifest and report drift from it
+        let manifest = match read_manifest(matches.value_of("MANIFEST").unwrap()) {
+            Ok(m) => m,
+            Err(e) => {
+                println!(
+                    "{} {}",
+                    Color::Red.paint("The manifest could not be read:"),
+                    e
+                );
+                process::exit(1);
+            }
+        };
+
+        tasks::sync(
+            package_managers,
+            distribution,
+            &manifest,
+            &matches
+                .values_of("group")
+                .map(|g| g.collect::<Vec<&str>>())
+                .unwrap_or_default(),
+            matches.is_present("prune"),
+            matches.is_present("check"),
+            matches.is_present("yes"),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("export") {
+        // Export explicitly installed packages across relevant package managers
+        tasks::export(
+            package_managers,
+            distribution,
+            &match matches.value_of("format") {
+                Some("brewfile") => ExportFormat::Brewfile,
+                Some("flatpak") => ExportFormat::Flatpak,
+                Some("plain") => ExportFormat::Plain,
+                _ => ExportFormat::Manifest,
+            },
+            matches.value_of("output"),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("upgrade") {
+        // Upgrade packages across relevant package managers
+        tasks::upgrade(
+            package_managers,
+            matches.value_of("PACKAGE"),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("hold") {
+        // Hold the package through the package manager it is installed through
+        tasks::hold(
+            package_managers,
+            matches.value_of("PACKAGE"),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("unhold") {
+        // Release the package through the package manager it is held through
+        tasks::unhold(
+            package_managers,
+            matches.value_of("PACKAGE").unwrap(),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("autoremove") {
+        // Remove orphaned dependencies across relevant package managers
+        tasks::autoremove(
+            package_managers,
+            matches.is_present("yes"),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("clean") {
+        // Clean package caches across relevant package managers
+        tasks::clean(
+            package_managers,
+            matches.is_present("yes"),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("repo") {
+        if let Some(matches) = matches.subcommand_matches("list") {
+            // List repositories across relevant package managers
+            tasks::repo_list(package_managers, matches.value_of("backend"));
+        } else if let Some(matches) = matches.subcommand_matches("add") {
+            // Add the repository through the package manager that takes it
+            match parse_repository(
+                &matches
+                    .values_of("REPOSITORY")
+                    .unwrap()
+                    .collect::<Vec<&str>>(),
+                matches.value_of("name"),
+                matches.value_of("key"),
+            ) {
+                Ok(repository) => tasks::repo_add(
+                    package_managers,
+                    &repository,
+                    matches.value_of("backend"),
+                    &get_options(matches),
+                ),
+                Err(e) => println!("{}", Color::Red.paint(e)),
+            }
+        } else if let Some(matches) = matches.subcommand_matches("remove") {
+            // Remove the repository from the package manager it is configured for
+            tasks::repo_remove(
+                package_managers,
+                matches.value_of("REPOSITORY").unwrap(),
+                matches.value_of("backend"),
+                &get_options(matches),
+            );
+        }
+    } else if matches.subcommand_matches("outdated").is_some() {
+        // List pending updates across relevant package managers
+        match tasks::outdated(package_managers) {
+            Ok(0) => (),
+            Ok(_) => process::exit(OUTDATED_EXIT_CODE),
+            Err(e) => {
+                println!(
+                    "{} {}",
+                    Color::Red.paint("Updates could not be checked through every package manager:"),
+                    e
+                );
+                process::exit(1);
+            }
+        }
+    } else if let Some(matches) = matches.subcommand_matches("provides") {
+        // Find packages that provide the command across relevant package managers
+        tasks::provides(package_managers, matches.value_of("COMMAND").unwrap());
+    } else if let Some(matches) = matches.subcommand_matches("owns") {
+        // Find the package that owns the file across relevant package managers
+        tasks::owns(package_managers, matches.value_of("FILE").unwrap());
+    } else if let Some(matches) = matches.subcommand_matches("deps") {
+        // Show the dependencies of the package through the package manager it is installed through
+        tasks::deps(
+            package_managers,
+            matches.value_of("PACKAGE").unwrap(),
+            matches.value_of("depth").unwrap().parse().unwrap(),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("rdeps") {
+        // Show the dependents of the package through the package manager it is installed through
+        tasks::rdeps(
+            package_managers,
+            matches.value_of("PACKAGE").unwrap(),
+            matches.value_of("depth").unwrap().parse().unwrap(),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("hook") {
+        // Print the hook for the shell
+        print!("{}", get_hook(matches.value_of("SHELL").unwrap()).unwrap());
+    } else if let Some(matches) = matches.subcommand_matches("history") {
+        // Show operations recorded in the journal
+        tasks::history(
+            matches.value_of("package"),
+            matches.value_of("backend"),
+            match matches.value_of("operation") {
+                Some("install") => Some(Operation::Install),
+                Some("uninstall") => Some(Operation::Uninstall),
+                Some("upgrade") => Some(Operation::Upgrade),
+                _ => None,
+            },
+            matches.value_of("limit").and_then(|l| l.parse().ok()),
+            matches.is_present("commands"),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("undo") {
+        // Reverse an operation recorded in the journal
+        tasks::undo(
+            package_managers,
+            matches.value_of("ID").and_then(|i| i.parse().ok()),
+            &get_options(matches),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("list") {
+        // List installed packages across relevant package managers
+        tasks::list(
+            package_managers,
+            &if matches.is_present("dependencies") {
+                InstallReason::Dependency
+            } else if matches.is_present("all") {
+                InstallReason::Any
+            } else {
+                InstallReason::Explicit
+            },
+            matches.value_of("backend"),
+        );
+    } else if let Some(matches) = matches.subcommand_matches("info") {
+        if let Some(package) = matches.value_of("PACKAGE") {
+            // Print information about the package across relevant package managers
+            tasks::info(package_managers, package);
+        } else if matches.is_present("system") {
+            // Print a report of the current system
+            tasks::info_system(package_managers, distribution, &get_options(matches));
+        }
+    } else {
+        // Ask to be run with a command
+        println!("{}", Color::Red.paint("Please run foraget with a command!"));
+    }
+}
+
+/// Generates the command-line interface of foraget.
+fn get_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(crate_name!())
         .version(crate_version!())
         .author(crate_authors!())
         .about(crate_description!())
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .global(true)
+                .help("Show the commands that would change the system instead of running them"),
+        )
         .subcommand(SubCommand::with_name("init").about("Install additional package sources"))
         .subcommand(
             SubCommand::with_name("search")
@@ -68,44 +337,539 @@ fn run(package_managers: &Vec<PackageManager>) {
                         .help("The package to search")
                         .required(true)
                         .index(1),
+                )
+                .arg(
+                    Arg::with_name("container")
+                        .long("container")
+                        .value_name("NAME")
+                        .help(
+                            "Also search package managers inside a distrobox or toolbox container",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("root")
+                        .long("root")
+                        .value_name("PATH")
+                        .help("Search only package managers that can target an alternate root directory"),
                 ),
         )
         .subcommand(
             SubCommand::with_name("install")
-                .about("Install a package")
+                .about("Install packages")
                 .arg(
                     Arg::with_name("PACKAGE")
-                        .help("The package to install")
+                        .help("The packages, as name or name@version, or paths to local package files, to install")
                         .required(true)
+                        .multiple(true)
                         .index(1),
+                )
+                .arg(
+                    Arg::with_name("apply-live")
+                        .long("apply-live")
+                        .help("Apply layered packages to the running system where supported"),
+                )
+                .arg(
+                    Arg::with_name("container")
+                        .long("container")
+                        .value_name("NAME")
+                        .help("Install inside a distrobox or toolbox container"),
+                )
+                .arg(
+                    Arg::with_name("export")
+                        .long("export")
+                        .requires("container")
+                        .help("Export the installed application or binary to the host"),
+                )
+                .arg(
+                    Arg::with_name("root")
+                        .long("root")
+                        .value_name("PATH")
+                        .help("Install into an alternate root directory"),
                 ),
         )
         .subcommand(
             SubCommand::with_name("uninstall")
-                .about("Uninstall a package if installed")
+                .about("Uninstall packages if installed")
                 .arg(
                     Arg::with_name("PACKAGE")
-                        .help("The package to uninstall")
+                        .help("The packages to uninstall")
                         .required(true)
+                        .multiple(true)
                         .index(1),
+                )
+                .arg(
+                    Arg::with_name("yes")
+                        .long("yes")
+                        .short("y")
+                        .help("Uninstall without asking when other packages depend on these"),
+                )
+                .arg(
+                    Arg::with_name("root")
+                        .long("root")
+                        .value_name("PATH")
+                        .help("Uninstall from an alternate root directory"),
                 ),
         )
-        .get_matches();
+        .subcommand(
+            SubCommand::with_name("run")
+                .about("Run an installed package through the source it was installed from")
+                .setting(AppSettings::TrailingVarArg)
+                .setting(AppSettings::AllowLeadingHyphen)
+                .arg(
+                    Arg::with_name("PACKAGE")
+                        .help("The package to run")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("ARGS")
+                        .help("Arguments to pass on to the package")
+                        .multiple(true)
+                        .allow_hyphen_values(true)
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("sync")
+                .about("Install packages declared in a manifest and report drift from it")
+                .arg(
+                    Arg::with_name("MANIFEST")
+                        .help("The TOML manifest that declares packages")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("group")
+                        .long("group")
+                        .value_name("NAME")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Sync only packages in the named group"),
+                )
+                .arg(
+                    Arg::with_name("prune")
+                        .long("prune")
+                        .help("Remove explicitly installed packages not declared in the manifest"),
+                )
+                .arg(
+                    Arg::with_name("yes")
+                        .long("yes")
+                        .short("y")
+                        .requires("prune")
+                        .help("Remove without asking after the preview"),
+                )
+                .arg(
+                    Arg::with_name("check")
+                        .long("check")
+                        .conflicts_with("prune")
+                        .help("Only report drift from the manifest, without changing the system"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("Export explicitly installed packages into a manifest or another format")
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .possible_values(&["manifest", "brewfile", "flatpak", "plain"])
+                        .default_value("manifest")
+                        .help("The format to export packages in"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("FILE")
+                        .help("The file to write to, instead of the standard output"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("upgrade")
+                .about("Upgrade packages across sources")
+                .arg(
+                    Arg::with_name("PACKAGE")
+                        .help("The package to upgrade, instead of all packages")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("root")
+                        .long("root")
+                        .value_name("PATH")
+                        .help("Upgrade packages in an alternate root directory"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("hold")
+                .about("Hold a package against upgrades, or list held packages")
+                .arg(
+                    Arg::with_name("PACKAGE")
+                        .help("The package to hold")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("root")
+                        .long("root")
+                        .value_name("PATH")
+                        .help("Refused, as holds apply to the host"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("unhold")
+                .about("Release a held package for upgrades")
+                .arg(
+                    Arg::with_name("PACKAGE")
+                        .help("The package to release")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("root")
+                        .long("root")
+                        .value_name("PATH")
+                        .help("Refused, as holds apply to the host"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("autoremove")
+                .about("Remove orphaned dependencies across sources")
+                .arg(
+                    Arg::with_name("yes")
+                        .long("yes")
+                        .short("y")
+                        .help("Remove without asking after the preview"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("clean")
+                .about("Clean package caches across sources")
+                .arg(
+                    Arg::with_name("yes")
+                        .long("yes")
+                        .short("y")
+                        .help("Clean without asking after the preview"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("repo")
+                .about("Manage repositories that packages are installed from")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List repositories across sources")
+                        .arg(
+                            Arg::with_name("backend")
+                                .long("backend")
+                                .value_name("NAME")
+                                .help("List repositories of only the named package manager"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Add a repository like ppa:owner/name, copr:owner/project, owner/tap or a URL")
+                        .arg(
+                            Arg::with_name("REPOSITORY")
+                                .help("The repository to add, with the suite and components after the URL for apt")
+                                .required(true)
+                                .multiple(true)
+                                .index(1),
+                        )
+                        .arg(
+                            Arg::with_name("name")
+                                .long("name")
+                                .value_name("NAME")
+                                .help("The name to add a repository at a URL by, instead of one made out of it"),
+                        )
+                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .value_name("URL")
+                                .help("The URL of the key the repository is signed with, for apt"),
+                        )
+                        .arg(
+                            Arg::with_name("backend")
+                                .long("backend")
+                                .value_name("NAME")
+                                .help("Add the repository through the named package manager"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Remove a repository by its listed name, or a hosted one")
+                        .arg(
+                            Arg::with_name("REPOSITORY")
+                                .help("The repository to remove")
+                                .required(true)
+                                .index(1),
+                        )
+                        .arg(
+                            Arg::with_name("backend")
+                                .long("backend")
+                                .value_name("NAME")
+                                .help("Remove the repository through the named package manager"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("outdated")
+                .about("List pending updates across sources without applying them"),
+        )
+        .subcommand(
+            SubCommand::with_name("provides")
+                .about("Find packages that provide a command or a file")
+                .arg(
+                    Arg::with_name("COMMAND")
+                        .help("The command, or the path of the file, to find packages for")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("owns")
+                .about("Find the package that installed a file")
+                .arg(
+                    Arg::with_name("FILE")
+                        .help("The path of the file to find the package for")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("deps")
+                .about("Show the tree of packages that a package depends on")
+                .arg(
+                    Arg::with_name("PACKAGE")
+                        .help("The package to show dependencies of")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("depth")
+                        .long("depth")
+                        .value_name("LEVELS")
+                        .default_value("2")
+                        .help("The number of levels of the tree to show")
+                        .validator(|v| {
+                            v.parse::<usize>()
+                                .map(|_| ())
+                                .map_err(|_| String::from("The depth must be a number"))
+                        }),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("rdeps")
+                .about("Show the tree of installed packages that depend on a package")
+                .arg(
+                    Arg::with_name("PACKAGE")
+                        .help("The package to show dependents of")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("depth")
+                        .long("depth")
+                        .value_name("LEVELS")
+                        .default_value("2")
+                        .help("The number of levels of the tree to show")
+                        .validator(|v| {
+                            v.parse::<usize>()
+                                .map(|_| ())
+                                .map_err(|_| String::from("The depth must be a number"))
+                        }),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("hook")
+                .about("Print a shell hook that suggests packages for commands not found")
+                .arg(
+                    Arg::with_name("SHELL")
+                        .help("The shell to print the hook for")
+                        .possible_values(&SHELLS)
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("history")
+                .about("Show operations carried out through foraget")
+                .arg(
+                    Arg::with_name("package")
+                        .long("package")
+                        .value_name("NAME")
+                        .help("Show only operations on the named package"),
+                )
+                .arg(
+                    Arg::with_name("backend")
+                        .long("backend")
+                        .value_name("NAME")
+                        .help("Show only operations through the named package manager"),
+                )
+                .arg(
+                    Arg::with_name("operation")
+                        .long("operation")
+                        .value_name("OPERATION")
+                        .possible_values(&["install", "uninstall", "upgrade"])
+                        .help("Show only operations of a kind"),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .value_name("COUNT")
+                        .validator(|v| {
+                            v.parse::<usize>()
+                                .map(|_| ())
+                                .map_err(|_| String::from("The limit must be a number"))
+                        })
+                        .help("Show only the most recent operations"),
+                )
+                .arg(
+                    Arg::with_name("commands")
+                        .long("commands")
+                        .help("Show the commands that were run as well"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("undo")
+                .about("Reverse an operation carried out through foraget")
+                .arg(
+                    Arg::with_name("ID")
+                        .help("The ID of the operation in the history, instead of the last one")
+                        .validator(|v| {
+                            v.parse::<u64>()
+                                .map(|_| ())
+                                .map_err(|_| String::from("The ID must be a number"))
+                        })
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("list")
+                .about("List installed packages across sources")
+                .arg(
+                    Arg::with_name("dependencies")
+                        .long("dependencies")
+                        .conflicts_with("all")
+                        .help("List packages installed as dependencies instead"),
+                )
+                .arg(
+                    Arg::with_name("all")
+                        .long("all")
+                        .help("List packages installed explicitly as well as dependencies"),
+                )
+                .arg(
+                    Arg::with_name("backend")
+                        .long("backend")
+                        .value_name("NAME")
+                        .help("List packages from only the named package manager"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("info")
+                .about("Show information about a package or the system")
+                .setting(AppSettings::ArgRequiredElseHelp)
+                .arg(
+                    Arg::with_name("PACKAGE")
+                        .help("The package to show information about")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("system")
+                        .long("system")
+                        .conflicts_with("PACKAGE")
+                        .help("Show the detected platform, distribution and package managers"),
+                ),
+        )
+}
 
-    if let Some(_) = matches.subcommand_matches("init") {
-        // Init package sources
-        tasks::init();
-    } else if let Some(matches) = matches.subcommand_matches("search") {
-        // Search for the package across relevant package managers
-        tasks::search(&package_managers, matches.value_of("PACKAGE").unwrap());
-    } else if let Some(matches) = matches.subcommand_matches("install") {
-        // Prompt to install the package from one of the relevant package managers
-        tasks::install(&package_managers, matches.value_of("PACKAGE").unwrap());
-    } else if let Some(matches) = matches.subcommand_matches("uninstall") {
-        // Try uninstalling the package using one of the relevant package managers
-        tasks::uninstall(&package_managers, matches.value_of("PACKAGE").unwrap());
-    } else {
-        // Ask to be run with a command
-        println!("{}", Color::Red.paint("Please run foraget with a command!"));
+/// Gathers options for a task from the supplied arguments.
+///
+/// Ends the program with an appropriate message when a supplied container cannot be found, or the
+/// supplied alternate root is not a directory.
+fn get_options(matches: &ArgMatches) -> Options {
+    let container = matches.value_of("container").map(|name| {
+        get_container(name).unwrap_or_else(|| {
+            println!(
+                "{} {}",
+                Color::Red.paint("Could not find a distrobox or toolbox container named"),
+                Color::Yellow.paint(name)
+            );
+            process::exit(1);
+        })
+    });
+
+    let root = matches
+        .value_of("root")
+        .map(|path| match fs::canonicalize(path) {
+            Ok(root) if root.is_dir() => root.to_string_lossy().to_string(),
+            _ => {
+                println!(
+                    "{} {}",
+                    Color::Red.paint("The alternate root is not a directory:"),
+                    Color::Yellow.paint(path)
+                );
+                process::exit(1);
+            }
+        });
+
+    let dry_run = matches.is_present("dry-run");
+    if dry_run {
+        println!(
+            "{}",
+            Color::Yellow.paint("This is a dry run, commands are shown instead of being run.")
+        );
+    }
+
+    Options {
+        apply_live: matches.is_present("apply-live"),
+        container,
+        export: matches.is_present("export"),
+        root,
+        privilege: get_privilege(),
+        aliases: get_aliases(),
+        dry_run,
+    }
+}
+
+/// Gets the aliases bundled with foraget along with those of the user.
+///
+/// Falls back to only the bundled aliases when those of the user cannot be read.
+fn get_aliases() -> Aliases {
+    match read_user_aliases() {
+        Ok(aliases) => get_bundled_aliases().merge(aliases),
+        Err(e) => {
+            println!(
+                "{} {}",
+                Color::Yellow.paint("Ignoring aliases that could not be read from"),
+                e
+            );
+            get_bundled_aliases()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_options_root_for_uninstall() {
+        let matches =
+            get_app().get_matches_from(vec!["foraget", "uninstall", "emacs", "--root", "/"]);
+
+        assert_eq!(
+            Some(String::from("/")),
+            get_options(matches.subcommand_matches("uninstall").unwrap()).root,
+            "Gets an alternate root for a subcommand other than install"
+        );
+    }
+
+    #[test]
+    fn get_options_root_for_upgrade() {
+        let matches = get_app().get_matches_from(vec!["foraget", "upgrade", "--root", "/"]);
+
+        assert_eq!(
+            Some(String::from("/")),
+            get_options(matches.subcommand_matches("upgrade").unwrap()).root,
+            "Gets an alternate root for upgrades"
+        );
     }
 }
@@ -0,0 +1,426 @@
+//! Contains logic around manifests that declare the packages a system should have.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::aliases::{get_override, Aliases};
+use crate::package_managers::PackageManager;
+use crate::platforms::Distribution;
+
+/// An enumeration of formats that installed packages can be exported in.
+#[derive(Debug, PartialEq)]
+pub enum ExportFormat {
+    /// A manifest that `foraget sync` accepts
+    Manifest,
+    /// A Brewfile that `brew bundle` accepts, with packages from Homebrew
+    Brewfile,
+    /// A list of application IDs from Flatpak
+    Flatpak,
+    /// Lists of packages under the package managers they are installed through
+    Plain,
+}
+
+/// A manifest that declares packages to be installed.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub packages: Vec<ManifestPackage>,
+}
+
+/// A package declared in a manifest.
+///
+/// Its name can be overridden for a distribution or a package manager through `names`, keyed by
+/// the distribution ID or the command name of the package manager.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct ManifestPackage {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub names: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// Creates a manifest out of packages installed through package managers.
+    ///
+    /// Packages from native package managers are declared without one and by their canonical
+    /// names, so that the manifest can be synced on other distributions. A package installed
+    /// through more than one native package manager is declared once.
+    pub fn from_installed(
+        installed: &[(&PackageManager, Vec<String>)],
+        aliases: &Aliases,
+        distribution: &Option<Distribution>,
+    ) -> Manifest {
+        let mut packages: Vec<ManifestPackage> = vec![];
+
+        for (package_manager, names) in installed {
+            let is_native = !package_manager.distributions.is_empty();
+
+            for name in names {
+                let name = if is_native {
+                    aliases.get_canonical_name(name, distribution, package_manager.command_name)
+                } else {
+                    name
+                };
+
+                if is_native
+                    && packages
+                        .iter()
+                        .any(|p| p.backend.is_none() && p.name == name)
+                {
+                    continue;
+                }
+
+                packages.push(ManifestPackage {
+                    name: name.to_string(),
+                    backend: if is_native {
+                        None
+                    } else {
+                        Some(package_manager.command_name.to_string())
+                    },
+                    group: None,
+                    names: BTreeMap::new(),
+                });
+            }
+        }
+
+        Manifest { packages }
+    }
+
+    /// Gets the packages that belong to one of the supplied groups, or all of them when no group
+    /// is supplied.
+    pub fn get_packages(&self, groups: &[&str]) -> Vec<&ManifestPackage> {
+        self.packages
+            .iter()
+            .filter(|p| {
+                groups.is_empty()
+                    || p.group
+                        .as_ref()
+                        .is_some_and(|g| groups.contains(&g.as_str()))
+            })
+            .collect()
+    }
+}
+
+impl ManifestPackage {
+    /// Gets the name of the package for a package manager on the supplied distribution.
+    ///
+    /// An override in the manifest wins over an alias for the package.
+    pub fn get_name<'a>(
+        &'a self,
+        distribution: &Option<Distribution>,
+        package_manager: &str,
+        aliases: &'a Aliases,
+    ) -> &'a str {
+        get_override(&self.names, distribution, package_manager)
+            .unwrap_or_else(|| aliases.get_name(&self.name, distribution, package_manager))
+    }
+}
+
+/// Generates the TOML text of a manifest.
+pub fn gen_manifest(manifest: &Manifest) -> Result<String, String> {
+    toml::to_string(manifest).map_err(|e| e.to_string())
+}
+
+/// The command that lists casks installed through Homebrew, which a Brewfile declares apart from
+/// formulae.
+pub const CASKS_COMMAND: &str = "brew list --cask -1";
+
+/// Generates the text of an export of installed packages in the supplied format.
+///
+/// Packages from Homebrew among the supplied casks are declared as casks in a Brewfile.
+pub fn gen_export(
+    installed: &[(&PackageManager, Vec<String>)],
+    casks: &[String],
+    format: &ExportFormat,
+    aliases: &Aliases,
+    distribution: &Option<Distribution>,
+) -> Result<String, String> {
+    let packages_of = |command_name: &str| {
+        installed
+            .iter()
+            .filter(|(p, _)| p.command_name == command_name)
+            .flat_map(|(_, names)| names.iter())
+            .collect::<Vec<&String>>()
+    };
+
+    match format {
+        ExportFormat::Manifest => {
+            gen_manifest(&Manifest::from_installed(installed, aliases, distribution))
+        }
+        ExportFormat::Brewfile => Ok(packages_of("brew")
+            .iter()
+            .map(|n| {
+                if casks.contains(n) {
+                    format!("cask \"{}\"\n", n)
+                } else {
+                    format!("brew \"{}\"\n", n)
+                }
+            })
+            .collect()),
+        ExportFormat::Flatpak => Ok(packages_of("flatpak")
+            .iter()
+            .map(|n| format!("{}\n", n))
+            .collect()),
+        ExportFormat::Plain => Ok(installed
+            .iter()
+            .map(|(p, names)| {
+                format!(
+                    "# {}\n{}",
+                    p.command_name,
+                    names.iter().map(|n| format!("{}\n", n)).collect::<String>()
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")),
+    }
+}
+
+/// Parses a manifest from its TOML text.
+pub fn parse_manifest(text: &str) -> Result<Manifest, String> {
+    toml::from_str(text).map_err(|e| e.to_string())
+}
+
+/// Reads a manifest from a file.
+pub fn read_manifest(path: &str) -> Result<Manifest, String> {
+    parse_manifest(&fs::read_to_string(path).map_err(|e| e.to_string())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aliases::get_bundled_aliases;
+    use crate::package_managers::{
+        get_known_package_managers_for_linux, get_known_package_managers_for_macos,
+    };
+    use crate::platforms::parse_os_release;
+
+    fn get_manifest() -> Manifest {
+        parse_manifest(
+            r#"
+            [[packages]]
+            name = "git"
+            group = "base"
+
+            [[packages]]
+            name = "fd"
+            group = "cli"
+            names = { debian = "fd-find" }
+
+            [[packages]]
+            name = "emacs"
+            backend = "flatpak"
+            names = { flatpak = "org.gnu.emacs" }
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_manifest_some_values() {
+        let manifest = get_manifest();
+
+        assert_eq!(3, manifest.packages.len(), "Parses every package");
+        assert_eq!(
+            Some(String::from("flatpak")),
+            manifest.packages[2].backend,
+            "Parses the preferred package manager of a package"
+        );
+    }
+
+    #[test]
+    fn parse_manifest_invalid() {
+        assert!(
+            parse_manifest("[[packages]]\nbackend = \"apt\"").is_err(),
+            "Fails to parse a package without a name"
+        );
+    }
+
+    #[test]
+    fn gen_manifest_round_trip() {
+        let manifest = get_manifest();
+
+        assert_eq!(
+            Ok(manifest),
+            parse_manifest(&gen_manifest(&get_manifest()).unwrap()),
+            "Generates a manifest that parses back the same"
+        );
+    }
+
+    #[test]
+    fn from_installed_some_values() {
+        let package_managers = get_known_package_managers_for_linux();
+        let pacman = &package_managers[0];
+        let paru = &package_managers[1];
+        let flatpak = package_managers
+            .iter()
+            .find(|p| p.command_name == "flatpak")
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                (String::from("git"), None),
+                (String::from("paru-bin"), None),
+                (String::from("org.gnu.emacs"), Some(String::from("flatpak"))),
+            ],
+            Manifest::from_installed(
+                &[
+                    (pacman, vec![String::from("git")]),
+                    (paru, vec![String::from("git"), String::from("paru-bin")]),
+                    (flatpak, vec![String::from("org.gnu.emacs")]),
+                ],
+                &Aliases::default(),
+                &None
+            )
+            .packages
+            .into_iter()
+            .map(|p| (p.name, p.backend))
+            .collect::<Vec<(String, Option<String>)>>(),
+            "Declares native packages once without a package manager, and others with one"
+        );
+    }
+
+    #[test]
+    fn gen_export_brewfile() {
+        let package_managers = get_known_package_managers_for_macos();
+
+        assert_eq!(
+            Ok(String::from("brew \"git\"\nbrew \"emacs\"\n")),
+            gen_export(
+                &[(
+                    &package_managers[0],
+                    vec![String::from("git"), String::from("emacs")]
+                )],
+                &[],
+                &ExportFormat::Brewfile,
+                &Aliases::default(),
+                &None
+            ),
+            "Generates a Brewfile with packages from Homebrew"
+        );
+    }
+
+    #[test]
+    fn gen_export_brewfile_casks() {
+        let package_managers = get_known_package_managers_for_macos();
+
+        assert_eq!(
+            Ok(String::from("brew \"git\"\ncask \"firefox\"\n")),
+            gen_export(
+                &[(
+                    &package_managers[0],
+                    vec![String::from("git"), String::from("firefox")]
+                )],
+                &[String::from("firefox")],
+                &ExportFormat::Brewfile,
+                &Aliases::default(),
+                &None
+            ),
+            "Generates a Brewfile that declares casks apart from formulae"
+        );
+    }
+
+    #[test]
+    fn gen_export_plain() {
+        let package_managers = get_known_package_managers_for_linux();
+
+        assert_eq!(
+            Ok(String::from("# pacman\ngit\nemacs\n\n# paru\nparu-bin\n")),
+            gen_export(
+                &[
+                    (
+                        &package_managers[0],
+                        vec![String::from("git"), String::from("emacs")]
+                    ),
+                    (&package_managers[1], vec![String::from("paru-bin")]),
+                ],
+                &[],
+                &ExportFormat::Plain,
+                &Aliases::default(),
+                &None
+            ),
+            "Generates lists of packages under their package managers"
+        );
+    }
+
+    #[test]
+    fn get_packages_in_groups() {
+        assert_eq!(
+            vec!["git"],
+            get_manifest()
+                .get_packages(&["base"])
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<&str>>(),
+            "Gets only the packages in the supplied groups"
+        );
+    }
+
+    #[test]
+    fn get_name_for_distribution() {
+        let ubuntu = parse_os_release(include_str!("../tests/fixtures/os-release/ubuntu"));
+
+        assert_eq!(
+            "fd-find",
+            get_manifest().packages[1].get_name(&ubuntu, "apt", &Aliases::default()),
+            "Gets the name overridden for a distribution the system is based on"
+        );
+    }
+
+    #[test]
+    fn get_name_for_package_manager() {
+        assert_eq!(
+            "org.gnu.emacs",
+            get_manifest().packages[2].get_name(&None, "flatpak", &Aliases::default()),
+            "Gets the name overridden for a package manager"
+        );
+    }
+
+    #[test]
+    fn get_name_from_aliases() {
+        let arch = parse_os_release(include_str!("../tests/fixtures/os-release/arch"));
+        let manifest = parse_manifest("[[packages]]\nname = \"pip\"").unwrap();
+
+        assert_eq!(
+            "python-pip",
+            manifest.packages[0].get_name(&arch, "pacman", &get_bundled_aliases()),
+            "Gets the name from an alias without an override"
+        );
+    }
+
+    #[test]
+    fn from_installed_canonical_names() {
+        let ubuntu = parse_os_release(include_str!("../tests/fixtures/os-release/ubuntu"));
+        let package_managers = get_known_package_managers_for_linux();
+        let apt = package_managers
+            .iter()
+            .find(|p| p.command_name == "apt")
+            .unwrap();
+
+        assert_eq!(
+            String::from("fd"),
+            Manifest::from_installed(
+                &[(apt, vec![String::from("fd-find")])],
+                &get_bundled_aliases(),
+                &ubuntu
+            )
+            .packages[0]
+                .name,
+            "Declares native packages by their canonical names"
+        );
+    }
+
+    #[test]
+    fn get_name_default() {
+        let arch = parse_os_release(include_str!("../tests/fixtures/os-release/arch"));
+
+        assert_eq!(
+            "fd",
+            get_manifest().packages[1].get_name(&arch, "pacman", &Aliases::default()),
+            "Gets the declared name without an override"
+        );
+    }
+}
@@ -1,9 +1,12 @@
 //! Contains information and logic around package managers for various environments.
 
+use crate::environment::quote;
+use std::path::Path;
+
 /// An abtract representation of a typical package manager.
 ///
 /// This struct defines basic properties of a package manager that includes searching, installing,
-/// uninstalling and running a package.
+/// uninstalling and running a package. Options left empty are not supported.
 pub struct PackageManager {
     pub command_name: &'static str,
     pub search_key: &'static str,
@@ -12,14 +15,384 @@ pub struct PackageManager {
     pub run_key: &'static str,
     pub no_confirm_key: &'static str,
     pub does_need_root: bool,
+    /// The option to install a local package file, if supported.
+    pub local_install_key: &'static str,
+    /// Extensions of the local package files it installs.
+    pub file_extensions: &'static [&'static str],
+    /// IDs of the distributions it is native to, or none for universal ones.
+    pub distributions: &'static [&'static str],
+    /// Whether packages layered onto an immutable system take effect only after a reboot.
+    pub does_need_reboot: bool,
+    /// The option to apply layered packages to the running system.
+    pub apply_live_key: &'static str,
+    /// The option to operate on an alternate root directory.
+    pub root_key: &'static str,
+    /// Lists packages installed explicitly.
+    pub list_explicit_command: &'static str,
+    /// Lists packages installed as dependencies.
+    pub list_dependencies_command: &'static str,
+    /// The option to show information about a package, with a `{remote}` placeholder where needed.
+    pub info_key: &'static str,
+    /// Labels of its information paired with the unified fields they stand for.
+    pub info_fields: &'static [(&'static str, &'static str)],
+    /// Gets the installed version of the package in `{}`.
+    pub installed_version_command: &'static str,
+    /// The option to refresh package metadata.
+    pub refresh_key: &'static str,
+    /// The option to upgrade every package.
+    pub upgrade_key: &'static str,
+    /// The option to upgrade a single package.
+    pub upgrade_package_key: &'static str,
+    /// Lists pending updates.
+    pub outdated_command: &'static str,
+    /// The format that pending updates are listed in.
+    pub outdated_format: UpdateFormat,
+    /// Lists packages that provide the file or command in `{}`.
+    pub provides_command: &'static str,
+    /// Lists packages that own the file at the path in `{}`.
+    pub owner_command: &'static str,
+    /// Installs particular versions, of the pinned packages in `{}`, or of the package with
+    /// `{name}` and `{version}` placeholders.
+    pub install_version_command: &'static str,
+    /// How a package is pinned to a version, with `{name}` and `{version}` placeholders, to install
+    /// several at once.
+    pub pin_format: &'static str,
+    /// Whether versions are numbers that can be ordered, rather than channels or commits.
+    pub does_order_versions: bool,
+    /// Lists remotes that offer the package in `{}` by its full identifier.
+    pub remote_command: &'static str,
+    /// Reverses the transaction in `{}`, or the latest change.
+    pub undo_transaction_command: &'static str,
+    /// Gets the ID of the latest transaction.
+    pub last_transaction_command: &'static str,
+    /// Lists available versions of the package in `{}`.
+    pub versions_command: &'static str,
+    /// Holds the package in `{}` against upgrades.
+    pub hold_command: &'static str,
+    /// Releases the held package in `{}`.
+    pub unhold_command: &'static str,
+    /// Lists held packages.
+    pub list_held_command: &'static str,
+    /// Lists orphaned dependencies, optionally followed by their sizes in bytes.
+    pub list_orphans_command: &'static str,
+    /// Removes orphaned dependencies.
+    pub autoremove_command: &'static str,
+    /// Lists cached items, optionally followed by their sizes in bytes.
+    pub list_cache_command: &'static str,
+    /// Cleans the package cache.
+    pub clean_command: &'static str,
+    /// Lists configured repositories as names followed by URLs.
+    pub list_repos_command: &'static str,
+    /// The prefix of hosted repositories, like `ppa:`.
+    pub hosted_repo_prefix: &'static str,
+    /// Adds the hosted repository in `{}`.
+    pub add_hosted_repo_command: &'static str,
+    /// Removes the hosted repository in `{}`.
+    pub remove_hosted_repo_command: &'static str,
+    /// The extension of repository files it adds from URLs.
+    pub repo_extension: &'static str,
+    /// Adds a repository at a URL, with `{name}`, `{url}`, `{components}` and `{key}` placeholders.
+    pub add_repo_command: &'static str,
+    /// Removes the repository in `{name}`.
+    pub remove_repo_command: &'static str,
+    /// Files that adding or removing the repository in `{name}` changes.
+    pub repo_files: &'static [&'static str],
+    /// Lists packages that the package in `{}` depends on.
+    pub deps_command: &'static str,
+    /// Lists installed packages that depend on the package in `{}`.
+    pub rdeps_command: &'static str,
+    /// The option to simulate a change without making it.
+    pub simulate_key: &'static str,
+    /// Previews an upgrade of every package, where it cannot be simulated.
+    pub simulate_upgrade_command: &'static str,
+    /// Lists packages that make up the base system, which are never pruned.
+    pub list_base_command: &'static str,
+}
+
+/// Fields of package information in a unified view, in the order they are shown.
+pub const INFO_FIELDS: [&str; 6] = [
+    "Version",
+    "Size",
+    "License",
+    "Homepage",
+    "Dependencies",
+    "Repository",
+];
+
+/// An enumeration of reasons packages are installed for.
+#[derive(Debug, PartialEq)]
+pub enum InstallReason {
+    Explicit,
+    Dependency,
+    Any,
+}
+
+/// An enumeration of formats that package managers list pending updates in.
+#[derive(Debug, PartialEq)]
+pub enum UpdateFormat {
+    /// `name old -> new`
+    Arrow,
+    /// `name/suite new arch [upgradable from: old]`
+    AptList,
+    /// `name-old < new`
+    ApkVersion,
+    /// `name (old) < new`
+    Brew,
+    /// `name new ...`, without the current version
+    Columns,
+}
+
+/// A pending update of a package.
+#[derive(Debug, PartialEq)]
+pub struct Update {
+    pub name: String,
+    pub current_version: String,
+    pub new_version: String,
+}
+
+impl PackageManager {
+    /// Returns the commands that list packages installed for the supplied reason.
+    ///
+    /// Reasons the package manager cannot tell packages apart by are left out.
+    pub fn get_list_commands(&self, reason: &InstallReason) -> Vec<&'static str> {
+        let commands = match reason {
+            InstallReason::Explicit => vec![self.list_explicit_command],
+            InstallReason::Dependency => vec![self.list_dependencies_command],
+            InstallReason::Any => vec![self.list_explicit_command, self.list_dependencies_command],
+        };
+
+        commands.into_iter().filter(|c| !c.is_empty()).collect()
+    }
+
+    /// Parses a line from the list of pending updates into an update.
+    ///
+    /// Returns `None` for lines that do not describe an update. The current version is left empty
+    /// when the package manager does not list it.
+    pub fn parse_update(&self, line: &str) -> Option<Update> {
+        let columns = line.split_whitespace().collect::<Vec<&str>>();
+        let update = |name: &str, current_version: &str, new_version: &str| {
+            Some(Update {
+                name: name.to_string(),
+                current_version: current_version.to_string(),
+                new_version: new_version.to_string(),
+            })
+        };
+
+        match self.outdated_format {
+            UpdateFormat::Arrow if columns.len() == 4 && columns[2] == "->" => {
+                update(columns[0], columns[1], columns[3])
+            }
+            UpdateFormat::AptList if columns.len() >= 6 && columns[0].contains('/') => update(
+                columns[0].split('/').next().unwrap_or_default(),
+                columns[5].trim_end_matches(']'),
+                columns[1],
+            ),
+            UpdateFormat::ApkVersion if columns.len() >= 3 => {
+                let mut parts = columns[0].rsplitn(3, '-');
+                let release = parts.next()?;
+                let version = parts.next()?;
+                let name = parts.next()?;
+
+                update(name, &format!("{}-{}", version, release), columns[2])
+            }
+            UpdateFormat::Brew if columns.len() >= 4 => update(
+                columns[0],
+                columns[1].trim_start_matches('(').trim_end_matches(')'),
+                columns[3],
+            ),
+            UpdateFormat::Columns if columns.len() >= 2 => {
+                // dnf qualifies package names with their architecture
+                let name = match columns[0].rsplit_once('.') {
+                    Some((name, "x86_64" | "aarch64" | "i686" | "noarch")) => name,
+                    _ => columns[0],
+                };
+
+                update(name, "", columns[1])
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses package information in the `Key: Value` format into fields of a unified view.
+    ///
+    /// Returns the fields in the order of `INFO_FIELDS`, with empty values for the ones the package
+    /// manager does not provide. Returns an empty list when none of the fields could be found.
+    pub fn parse_info(&self, lines: &[String]) -> Vec<(&'static str, String)> {
+        let pairs = lines
+            .iter()
+            .filter_map(|l| l.split_once(':'))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect::<Vec<(&str, &str)>>();
+
+        let fields = INFO_FIELDS
+            .iter()
+            .map(|field| {
+                let value = self
+                    .info_fields
+                    .iter()
+                    .filter(|(f, _)| f == field)
+                    .find_map(|(_, key)| pairs.iter().find(|(k, _)| k == key))
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default();
+
+                (*field, value)
+            })
+            .collect::<Vec<(&'static str, String)>>();
+
+        if fields.iter().all(|(_, v)| v.is_empty()) {
+            Vec::new()
+        } else {
+            fields
+        }
+    }
+
+    /// Gets the repository within a hosted one requested like `ppa:owner/name`, when the package
+    /// manager can add it.
+    ///
+    /// Package managers without a prefix for hosted repositories, like Homebrew for taps, take
+    /// them without one.
+    pub fn get_hosted_repo<'a>(&self, repository: &'a str) -> Option<&'a str> {
+        if self.add_hosted_repo_command.is_empty() {
+            None
+        } else if self.hosted_repo_prefix.is_empty() {
+            Some(repository).filter(|r| !r.contains(':'))
+        } else {
+            repository.strip_prefix(self.hosted_repo_prefix)
+        }
+    }
+
+    /// Returns whether the package manager can add a repository from a file at the given URL, like
+    /// a `.repo` file.
+    pub fn can_add_repo_file(&self, url: &str) -> bool {
+        !self.add_repo_command.is_empty()
+            && !self.repo_extension.is_empty()
+            && url.to_lowercase().ends_with(self.repo_extension)
+    }
+
+    /// Gets the files that the supplied command for adding or removing a repository, like
+    /// `add_repo_command`, changes for the repository with the given name.
+    pub fn get_repo_files(&self, command: &str, name: &str) -> Vec<String> {
+        self.repo_files
+            .iter()
+            .filter(|f| command.contains(*f))
+            .map(|f| f.replace("{name}", name))
+            .collect()
+    }
+
+    /// Generates a command that simulates the supplied one without changing the system, when the
+    /// package manager can.
+    ///
+    /// Only commands that run the package manager alone are simulated, with the option to skip
+    /// confirmation replaced by the one to simulate. Simulations run without elevated privileges,
+    /// and commands that refresh package databases, like `pacman -Syu`, are never simulated, except
+    /// for an upgrade of every package that the package manager has a separate command to preview.
+    pub fn gen_simulated_command(&self, command: &str) -> Option<String> {
+        let start = command.find(&format!("{} ", self.command_name))?;
+        if self.simulate_key.is_empty() || ["&&", "|", ";"].iter().any(|o| command.contains(o)) {
+            return None;
+        }
+
+        let command = command[start..].trim_end();
+        let command = match command.strip_suffix(self.no_confirm_key) {
+            Some(c) if !self.no_confirm_key.is_empty() => c.trim_end(),
+            _ => command,
+        };
+
+        if command == format!("{} {}", self.command_name, self.upgrade_key)
+            && !self.simulate_upgrade_command.is_empty()
+        {
+            return Some(self.simulate_upgrade_command.to_string());
+        }
+        if command
+            .split_whitespace()
+            .any(|w| w.starts_with("-S") && w.contains('y'))
+        {
+            return None;
+        }
+
+        Some(format!("{} {}", command, self.simulate_key))
+    }
+
+    /// Returns whether the package manager can install a local package file with the given path.
+    pub fn can_install_file(&self, file_path: &str) -> bool {
+        !self.local_install_key.is_empty()
+            && self
+                .file_extensions
+                .iter()
+                .any(|e| file_path.to_lowercase().ends_with(e))
+    }
+
+    /// Gets the name of the package in a local package file from the name of the file, like
+    /// `emacs` for `emacs-29.1-1-x86_64.pkg.tar.zst` or `emacs_29.1_amd64.deb`.
+    pub fn get_file_package_name(&self, file_path: &str) -> Option<String> {
+        let file_name = Path::new(file_path).file_name()?.to_string_lossy();
+        let extension = self
+            .file_extensions
+            .iter()
+            .filter(|e| file_name.to_lowercase().ends_with(*e))
+            .max_by_key(|e| e.len())?;
+        let stem = &file_name[..file_name.len() - extension.len()];
+
+        // Versions follow the name after an underscore, or after a hyphen and a digit
+        let name = stem.split('_').next().unwrap_or(stem);
+        let end = name
+            .match_indices('-')
+            .map(|(i, _)| i)
+            .find(|i| name[i + 1..].starts_with(|c: char| c.is_ascii_digit()))
+            .unwrap_or(name.len());
+
+        Some(name[..end].to_string()).filter(|n| !n.is_empty())
+    }
 }
 
 /// A trait for generating commands for a package manager.
+///
+/// Commands are generated without elevating privileges, which is left to the caller for package
+/// managers that need root.
 pub trait Installer {
     fn gen_search_command(&self, package: &str) -> String;
     fn gen_install_command(&self, package: &str) -> String;
+    fn gen_install_packages_command(&self, packages: &[&str]) -> String;
+    fn gen_local_install_command(&self, file_path: &str) -> String;
     fn gen_uninstall_command(&self, package: &str) -> String;
+    fn gen_uninstall_packages_command(&self, packages: &[&str]) -> String;
     fn gen_run_command(&self, package: &str) -> String;
+    fn gen_rooted_command(&self, command: &str, root: &str) -> Result<String, String>;
+    fn gen_refresh_command(&self) -> String;
+    fn gen_upgrade_command(&self) -> String;
+    fn gen_upgrade_package_command(&self, package: &str) -> String;
+    fn gen_info_command(&self, package: &str) -> String;
+    fn gen_installed_version_command(&self, package: &str) -> String;
+    fn gen_provides_command(&self, path: &str) -> String;
+    fn gen_owner_command(&self, path: &str) -> String;
+    fn gen_install_version_command(&self, package: &str, version: &str) -> String;
+    fn gen_install_versions_command(&self, packages: &[(&str, &str)]) -> String;
+    fn gen_versions_command(&self, package: &str) -> String;
+    fn gen_remote_command(&self, package: &str) -> String;
+    fn gen_undo_transaction_command(&self, transaction: &str) -> String;
+    fn gen_hold_command(&self, package: &str) -> String;
+    fn gen_unhold_command(&self, package: &str) -> String;
+    fn gen_add_hosted_repo_command(&self, repository: &str) -> String;
+    fn gen_remove_hosted_repo_command(&self, repository: &str) -> String;
+    fn gen_add_repo_command(
+        &self,
+        name: &str,
+        url: &str,
+        components: &[&str],
+        key: Option<&str>,
+    ) -> String;
+    fn gen_remove_repo_command(&self, name: &str) -> String;
+    fn gen_deps_command(&self, package: &str) -> String;
+    fn gen_rdeps_command(&self, package: &str) -> String;
+}
+
+/// Quotes each of the supplied package names for a shell, and joins them with spaces.
+fn quote_all(package_names: &[&str]) -> String {
+    package_names
+        .iter()
+        .map(|p| quote(p))
+        .collect::<Vec<String>>()
+        .join(" ")
 }
 
 /// Implementation of trait `Installer` for struct `PackageManager`.
@@ -29,39 +402,204 @@ impl Installer for PackageManager {
     }
 
     fn gen_install_command(&self, package_name: &str) -> String {
-        if self.does_need_root {
-            format!(
-                "sudo {} {} {} {}",
-                self.command_name, self.install_key, package_name, self.no_confirm_key,
-            )
-        } else {
-            format!(
-                "{} {} {} {}",
-                self.command_name, self.install_key, package_name, self.no_confirm_key,
-            )
-        }
+        self.gen_install_packages_command(&[package_name])
+    }
+
+    fn gen_install_packages_command(&self, package_names: &[&str]) -> String {
+        format!(
+            "{} {} {} {}",
+            self.command_name,
+            self.install_key,
+            quote_all(package_names),
+            self.no_confirm_key,
+        )
+    }
+
+    fn gen_local_install_command(&self, file_path: &str) -> String {
+        format!(
+            "{} {} {} {}",
+            self.command_name,
+            self.local_install_key,
+            quote(file_path),
+            self.no_confirm_key,
+        )
     }
 
     fn gen_uninstall_command(&self, package_name: &str) -> String {
-        if self.does_need_root {
-            format!(
-                "sudo {} {} {} {}",
-                self.command_name, self.uninstall_key, package_name, self.no_confirm_key,
-            )
-        } else {
-            format!(
-                "{} {} {} {}",
-                self.command_name, self.uninstall_key, package_name, self.no_confirm_key,
-            )
-        }
+        self.gen_uninstall_packages_command(&[package_name])
+    }
+
+    fn gen_uninstall_packages_command(&self, package_names: &[&str]) -> String {
+        format!(
+            "{} {} {} {}",
+            self.command_name,
+            self.uninstall_key,
+            quote_all(package_names),
+            self.no_confirm_key,
+        )
     }
 
     fn gen_run_command(&self, package_name: &str) -> String {
-        if self.run_key != "" {
+        if !self.run_key.is_empty() {
             format!("{} {} {}", self.command_name, self.run_key, package_name)
         } else {
-            format!("{}", package_name)
+            package_name.to_string()
+        }
+    }
+
+    fn gen_refresh_command(&self) -> String {
+        format!("{} {}", self.command_name, self.refresh_key)
+    }
+
+    fn gen_upgrade_command(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.command_name, self.upgrade_key, self.no_confirm_key
+        )
+    }
+
+    fn gen_upgrade_package_command(&self, package_name: &str) -> String {
+        format!(
+            "{} {} {} {}",
+            self.command_name, self.upgrade_package_key, package_name, self.no_confirm_key
+        )
+    }
+
+    fn gen_info_command(&self, package_name: &str) -> String {
+        format!(
+            "{} {} {}",
+            self.command_name,
+            self.info_key,
+            quote(package_name)
+        )
+    }
+
+    fn gen_installed_version_command(&self, package_name: &str) -> String {
+        self.installed_version_command
+            .replace("{}", &quote(package_name))
+    }
+
+    fn gen_provides_command(&self, path: &str) -> String {
+        self.provides_command.replace("{}", &quote(path))
+    }
+
+    fn gen_owner_command(&self, path: &str) -> String {
+        self.owner_command.replace("{}", &quote(path))
+    }
+
+    fn gen_install_version_command(&self, package_name: &str, version: &str) -> String {
+        if self.pin_format.is_empty() {
+            self.install_version_command
+                .replace("{name}", &quote(package_name))
+                .replace("{version}", &quote(version))
+        } else {
+            self.gen_install_versions_command(&[(package_name, version)])
+        }
+    }
+
+    fn gen_install_versions_command(&self, packages: &[(&str, &str)]) -> String {
+        if self.pin_format.is_empty() {
+            return packages
+                .iter()
+                .map(|(n, v)| self.gen_install_version_command(n, v))
+                .collect::<Vec<String>>()
+                .join(" && ");
+        }
+
+        let pins = packages
+            .iter()
+            .map(|(n, v)| {
+                self.pin_format
+                    .replace("{name}", &quote(n))
+                    .replace("{version}", &quote(v))
+            })
+            .collect::<Vec<String>>();
+        self.install_version_command.replace("{}", &pins.join(" "))
+    }
+
+    fn gen_versions_command(&self, package_name: &str) -> String {
+        self.versions_command.replace("{}", &quote(package_name))
+    }
+
+    fn gen_remote_command(&self, package_name: &str) -> String {
+        self.remote_command.replace("{}", &quote(package_name))
+    }
+
+    fn gen_undo_transaction_command(&self, transaction: &str) -> String {
+        self.undo_transaction_command
+            .replace("{}", &quote(transaction))
+    }
+
+    fn gen_hold_command(&self, package_name: &str) -> String {
+        self.hold_command.replace("{}", &quote(package_name))
+    }
+
+    fn gen_unhold_command(&self, package_name: &str) -> String {
+        self.unhold_command.replace("{}", &quote(package_name))
+    }
+
+    fn gen_add_hosted_repo_command(&self, repository: &str) -> String {
+        self.add_hosted_repo_command
+            .replace("{}", &quote(repository))
+    }
+
+    fn gen_remove_hosted_repo_command(&self, repository: &str) -> String {
+        self.remove_hosted_repo_command
+            .replace("{}", &quote(repository))
+    }
+
+    fn gen_add_repo_command(
+        &self,
+        name: &str,
+        url: &str,
+        components: &[&str],
+        key: Option<&str>,
+    ) -> String {
+        self.add_repo_command
+            .replace("{name}", &quote(name))
+            .replace("{url}", &quote(url))
+            .replace("{components}", &quote(&components.join(" ")))
+            .replace("{key}", &quote(key.unwrap_or_default()))
+    }
+
+    fn gen_remove_repo_command(&self, name: &str) -> String {
+        self.remove_repo_command.replace("{name}", &quote(name))
+    }
+
+    fn gen_deps_command(&self, package_name: &str) -> String {
+        self.deps_command.replace("{}", &quote(package_name))
+    }
+
+    fn gen_rdeps_command(&self, package_name: &str) -> String {
+        self.rdeps_command.replace("{}", &quote(package_name))
+    }
+
+    fn gen_rooted_command(&self, command: &str, root: &str) -> Result<String, String> {
+        if self.root_key.is_empty() {
+            return Err(format!(
+                "{} cannot target an alternate root",
+                self.command_name
+            ));
         }
+
+        let invocation = format!("{} ", self.command_name);
+        let rooted_invocation = format!("{} {}={} ", self.command_name, self.root_key, quote(root));
+        let rest = command.strip_prefix(&invocation).ok_or_else(|| {
+            format!(
+                "the command does not run {} to target an alternate root with",
+                self.command_name
+            )
+        })?;
+
+        // Point every command chained after the first one to the root as well
+        Ok(["&& ", "|| ", "; "]
+            .iter()
+            .fold(format!("{}{}", rooted_invocation, rest), |c, o| {
+                c.replace(
+                    &format!("{}{}", o, invocation),
+                    &format!("{}{}", o, rooted_invocation),
+                )
+            }))
     }
 }
 
@@ -75,6 +613,58 @@ fn get_pacman() -> PackageManager {
         run_key: "",
         no_confirm_key: "--noconfirm",
         does_need_root: true,
+        local_install_key: "-U",
+        file_extensions: &[".pkg.tar.zst", ".pkg.tar.xz", ".pkg.tar.gz"],
+        distributions: &["arch"],
+        does_need_reboot: false,
+        apply_live_key: "",
+        root_key: "--sysroot",
+        list_explicit_command: "pacman -Qeq",
+        list_dependencies_command: "pacman -Qdq",
+        info_key: "-Si",
+        info_fields: &[
+            ("Version", "Version"),
+            ("Size", "Installed Size"),
+            ("License", "Licenses"),
+            ("Homepage", "URL"),
+            ("Dependencies", "Depends On"),
+            ("Repository", "Repository"),
+        ],
+        installed_version_command: "pacman -Q {} 2>/dev/null | awk '{ print $2 }'",
+        refresh_key: "",
+        upgrade_key: "-Syu",
+        upgrade_package_key: "-S",
+        outdated_command: "checkupdates",
+        outdated_format: UpdateFormat::Arrow,
+        provides_command: "pkgfile -- {} 2>/dev/null || pacman -Fq -- {} 2>/dev/null",
+        owner_command: "pacman -Qoq -- {} 2>/dev/null",
+        install_version_command: "pacman -U --noconfirm {}",
+        pin_format: "/var/cache/pacman/pkg/{name}-{version}-*.pkg.tar.zst",
+        does_order_versions: true,
+        remote_command: "",
+        undo_transaction_command: "",
+        last_transaction_command: "",
+        versions_command: "ls /var/cache/pacman/pkg 2>/dev/null | sed -nE \"s/^\"{}\"-([^-]+-[^-]+)-[^-]+\\.pkg\\.tar\\.zst$/\\1/p\" | sort -ruV",
+        hold_command: "touch /etc/pacman.d/foraget-holds.conf && (grep -qxF 'Include = /etc/pacman.d/foraget-holds.conf' /etc/pacman.conf || sed -i '/^\\[options\\]/a Include = /etc/pacman.d/foraget-holds.conf' /etc/pacman.conf) && (grep -qx \"IgnorePkg = \"{} /etc/pacman.d/foraget-holds.conf || echo \"IgnorePkg = \"{} >> /etc/pacman.d/foraget-holds.conf)",
+        unhold_command: "sed -i \"/^IgnorePkg = \"{}\"$/d\" /etc/pacman.d/foraget-holds.conf",
+        list_held_command: "pacman-conf IgnorePkg 2>/dev/null",
+        list_orphans_command: "pacman -Qdtq 2>/dev/null | xargs -r pacman -Qi 2>/dev/null | awk -F ' *: ' '$1 == \"Name\" { n = $2 } $1 == \"Installed Size\" { split($2, s, \" \"); m = s[2] == \"KiB\" ? 1024 : s[2] == \"MiB\" ? 1048576 : s[2] == \"GiB\" ? 1073741824 : 1; printf \"%s %d\\n\", n, s[1] * m }'",
+        autoremove_command: "pacman -Qdtq | pacman -Rns - --noconfirm",
+        list_cache_command: "paccache -dv 2>/dev/null | grep '^/' | xargs -r du -b 2>/dev/null | awk '{ print $2, $1 }'",
+        clean_command: "paccache -r",
+        list_repos_command: "pacman-conf --repo-list 2>/dev/null | while read -r repo; do echo \"$repo $(pacman-conf --repo \"$repo\" Server 2>/dev/null | head -n1)\"; done",
+        hosted_repo_prefix: "",
+        add_hosted_repo_command: "",
+        remove_hosted_repo_command: "",
+        repo_extension: "",
+        add_repo_command: "",
+        remove_repo_command: "",
+        repo_files: &[],
+        deps_command: "pactree -d 1 -u {} 2>/dev/null | tail -n +2",
+        rdeps_command: "pactree -r -d 1 -u {} 2>/dev/null | tail -n +2",
+        simulate_key: "--print",
+        simulate_upgrade_command: "pacman -Qu",
+        list_base_command: "pacman -Qgq 2>/dev/null; echo base; pacman -Qi base 2>/dev/null | sed -nE 's/^Depends On +: //p' | tr -s ' ' '\\n' | sed 's/[<>=].*//'",
     }
 }
 
@@ -88,6 +678,57 @@ fn get_paru() -> PackageManager {
         run_key: "",
         no_confirm_key: "--noconfirm",
         does_need_root: false,
+        local_install_key: "",
+        file_extensions: &[],
+        distributions: &["arch"],
+        does_need_reboot: false,
+        apply_live_key: "",
+        root_key: "",
+        list_explicit_command: "",
+        list_dependencies_command: "",
+        info_key: "-Si",
+        info_fields: &[
+            ("Version", "Version"),
+            ("License", "Licenses"),
+            ("Homepage", "URL"),
+            ("Dependencies", "Depends On"),
+            ("Repository", "Repository"),
+        ],
+        installed_version_command: "paru -Q {} 2>/dev/null | awk '{ print $2 }'",
+        refresh_key: "",
+        upgrade_key: "-Sua",
+        upgrade_package_key: "-S",
+        outdated_command: "paru -Qua",
+        outdated_format: UpdateFormat::Arrow,
+        provides_command: "",
+        owner_command: "",
+        install_version_command: "",
+        pin_format: "",
+        does_order_versions: false,
+        remote_command: "",
+        undo_transaction_command: "",
+        last_transaction_command: "",
+        versions_command: "",
+        hold_command: "",
+        unhold_command: "",
+        list_held_command: "",
+        list_orphans_command: "",
+        autoremove_command: "",
+        list_cache_command: "",
+        clean_command: "",
+        list_repos_command: "",
+        hosted_repo_prefix: "",
+        add_hosted_repo_command: "",
+        remove_hosted_repo_command: "",
+        repo_extension: "",
+        add_repo_command: "",
+        remove_repo_command: "",
+        repo_files: &[],
+        deps_command: "",
+        rdeps_command: "",
+        simulate_key: "",
+        simulate_upgrade_command: "",
+        list_base_command: "pacman -Qgq 2>/dev/null; echo base; pacman -Qi base 2>/dev/null | sed -nE 's/^Depends On +: //p' | tr -s ' ' '\\n' | sed 's/[<>=].*//'",
     }
 }
 
@@ -97,10 +738,120 @@ fn get_dnf() -> PackageManager {
         command_name: "dnf",
         search_key: "search",
         install_key: "install",
-        uninstall_key: "uninstall",
+        uninstall_key: "remove",
         run_key: "",
         no_confirm_key: "-y",
         does_need_root: true,
+        local_install_key: "install",
+        file_extensions: &[".rpm"],
+        distributions: &["fedora", "rhel"],
+        does_need_reboot: false,
+        apply_live_key: "",
+        root_key: "--installroot",
+        list_explicit_command: "dnf repoquery --userinstalled --qf '%{name}'",
+        list_dependencies_command: "dnf repoquery --installed --qf '%{name} %{reason}' | awk '$2 == \"dependency\" { print $1 }'",
+        info_key: "info",
+        info_fields: &[
+            ("Version", "Version"),
+            ("Size", "Size"),
+            ("License", "License"),
+            ("Homepage", "URL"),
+            ("Repository", "Repository"),
+        ],
+        installed_version_command: "rpm -q --quiet {} && rpm -q --qf '%{VERSION}-%{RELEASE}' {}",
+        refresh_key: "",
+        upgrade_key: "upgrade --refresh",
+        upgrade_package_key: "upgrade",
+        outdated_command: "dnf check-update -q",
+        outdated_format: UpdateFormat::Columns,
+        provides_command: "dnf repoquery -q --qf '%{name}' --whatprovides {}",
+        owner_command: "rpm -qf --quiet {} && rpm -qf --qf '%{NAME}\\n' {}",
+        install_version_command: "dnf install -y {} || dnf downgrade -y {}",
+        pin_format: "{name}-{version}",
+        does_order_versions: true,
+        remote_command: "",
+        undo_transaction_command: "dnf history undo {} -y",
+        last_transaction_command: "dnf history list 2>/dev/null | awk '$1 ~ /^[0-9]+$/ { print $1; exit }'",
+        versions_command: "dnf list --showduplicates -q {} 2>/dev/null | awk 'NR > 1 { sub(/^[0-9]+:/, \"\", $2); print $2 }' | sort -ruV",
+        hold_command: "dnf versionlock add {}",
+        unhold_command: "dnf versionlock delete {}",
+        list_held_command: "dnf versionlock list -q 2>/dev/null | sed -nE 's/^(.+)-[0-9]+:.*$/\\1/p'",
+        list_orphans_command: "dnf repoquery --installed --unneeded -q --qf '%{name} %{installsize}' 2>/dev/null",
+        autoremove_command: "dnf autoremove -y",
+        list_cache_command: "du -sb /var/cache/dnf 2>/dev/null | awk '{ print $2, $1 }'",
+        clean_command: "dnf clean all",
+        list_repos_command: "grep -H -m1 -E '^(baseurl|metalink|mirrorlist)=' /etc/yum.repos.d/*.repo 2>/dev/null | sed -E 's|^/etc/yum.repos.d/(.*)\\.repo:[a-z]+=|\\1 |'",
+        hosted_repo_prefix: "copr:",
+        add_hosted_repo_command: "dnf copr enable -y {}",
+        remove_hosted_repo_command: "dnf copr remove -y {}",
+        repo_extension: ".repo",
+        add_repo_command: "curl -fsSL {url} -o /etc/yum.repos.d/{name}.repo",
+        remove_repo_command: "rm -f /etc/yum.repos.d/{name}.repo",
+        repo_files: &["/etc/yum.repos.d/{name}.repo"],
+        deps_command: "dnf repoquery --requires --resolve -q --qf '%{name}\\n' {} 2>/dev/null | sort -u",
+        rdeps_command: "dnf repoquery --whatrequires {} --installed -q --qf '%{name}\\n' 2>/dev/null | sort -u",
+        simulate_key: "--assumeno",
+        simulate_upgrade_command: "",
+        list_base_command: "",
+    }
+}
+
+/// Generates an instance of 'rpm-ostree'.
+fn get_rpm_ostree() -> PackageManager {
+    PackageManager {
+        command_name: "rpm-ostree",
+        search_key: "search",
+        install_key: "install",
+        uninstall_key: "uninstall",
+        run_key: "",
+        no_confirm_key: "",
+        does_need_root: true,
+        local_install_key: "install",
+        file_extensions: &[".rpm"],
+        distributions: &["fedora"],
+        does_need_reboot: true,
+        apply_live_key: "--apply-live",
+        root_key: "",
+        list_explicit_command:
+            "rpm-ostree status --booted | sed -n 's/^ *LayeredPackages: //p' | tr ' ' '\\n'",
+        list_dependencies_command: "",
+        info_key: "",
+        info_fields: &[],
+        installed_version_command: "rpm -q --quiet {} && rpm -q --qf '%{VERSION}-%{RELEASE}' {}",
+        refresh_key: "",
+        upgrade_key: "upgrade",
+        upgrade_package_key: "",
+        outdated_command: "",
+        outdated_format: UpdateFormat::Columns,
+        provides_command: "",
+        owner_command: "rpm -qf --quiet {} && rpm -qf --qf '%{NAME}\\n' {}",
+        install_version_command: "",
+        pin_format: "",
+        does_order_versions: false,
+        remote_command: "",
+        undo_transaction_command: "rpm-ostree rollback",
+        last_transaction_command: "",
+        versions_command: "",
+        hold_command: "",
+        unhold_command: "",
+        list_held_command: "",
+        list_orphans_command: "",
+        autoremove_command: "",
+        list_cache_command: "",
+        clean_command: "",
+        list_repos_command: "grep -H -m1 -E '^(baseurl|metalink|mirrorlist)=' /etc/yum.repos.d/*.repo 2>/dev/null | sed -E 's|^/etc/yum.repos.d/(.*)\\.repo:[a-z]+=|\\1 |'",
+        hosted_repo_prefix: "",
+        add_hosted_repo_command: "",
+        remove_hosted_repo_command: "",
+        repo_extension: ".repo",
+        add_repo_command: "curl -fsSL {url} -o /etc/yum.repos.d/{name}.repo",
+        remove_repo_command: "rm -f /etc/yum.repos.d/{name}.repo",
+        repo_files: &["/etc/yum.repos.d/{name}.repo"],
+        deps_command: "",
+        rdeps_command: "",
+        simulate_key: "",
+        simulate_upgrade_command: "",
+        list_base_command: "",
     }
 }
 
@@ -110,10 +861,124 @@ fn get_apt() -> PackageManager {
         command_name: "apt",
         search_key: "search",
         install_key: "install",
-        uninstall_key: "uninstall",
+        uninstall_key: "remove",
         run_key: "",
         no_confirm_key: "-y",
         does_need_root: true,
+        local_install_key: "install",
+        file_extensions: &[".deb"],
+        distributions: &["debian"],
+        does_need_reboot: false,
+        apply_live_key: "",
+        root_key: "-o Dir",
+        list_explicit_command: "apt-mark showmanual",
+        list_dependencies_command: "apt-mark showauto",
+        info_key: "show",
+        info_fields: &[
+            ("Version", "Version"),
+            ("Size", "Installed-Size"),
+            ("Homepage", "Homepage"),
+            ("Dependencies", "Depends"),
+            ("Repository", "APT-Sources"),
+        ],
+        installed_version_command: "dpkg-query -W -f='${db:Status-Abbrev} ${Version}' {} 2>/dev/null | awk '$1 ~ /^.i/ { print $2 }'",
+        refresh_key: "update",
+        upgrade_key: "upgrade",
+        upgrade_package_key: "install --only-upgrade",
+        outdated_command: "apt list --upgradable 2>/dev/null",
+        outdated_format: UpdateFormat::AptList,
+        provides_command: "apt-file search -lF {} 2>/dev/null",
+        owner_command: "dpkg-query -S {} 2>/dev/null | grep -v '^diversion' | sed 's/: .*//; s/:[^,]*//g; s/, /\\n/g'",
+        install_version_command: "apt install -y --allow-downgrades {}",
+        pin_format: "{name}={version}",
+        does_order_versions: true,
+        remote_command: "",
+        undo_transaction_command: "",
+        last_transaction_command: "",
+        versions_command: "apt-cache madison {} 2>/dev/null | awk -F ' [|] ' '{ print $2 }'",
+        hold_command: "apt-mark hold {}",
+        unhold_command: "apt-mark unhold {}",
+        list_held_command: "apt-mark showhold",
+        list_orphans_command: "apt-get -s autoremove 2>/dev/null | awk '$1 == \"Remv\" { print $2 }' | xargs -r dpkg-query -W -f='${Package} ${Installed-Size}\\n' | awk '{ print $1, $2 * 1024 }'",
+        autoremove_command: "apt autoremove -y",
+        list_cache_command: "du -sb /var/cache/apt/archives 2>/dev/null | awk '{ print $2, $1 }'",
+        clean_command: "apt clean",
+        list_repos_command: "grep -H -E '^(deb|URIs:) ' /etc/apt/sources.list.d/*.list /etc/apt/sources.list.d/*.sources 2>/dev/null | sed -E 's#^/etc/apt/sources.list.d/(.*)\\.(list|sources):(deb (\\[[^]]*\\] )?|URIs: )#\\1 #'",
+        hosted_repo_prefix: "ppa:",
+        add_hosted_repo_command: "add-apt-repository -y ppa:{}",
+        remove_hosted_repo_command: "add-apt-repository -r -y ppa:{}",
+        repo_extension: "",
+        add_repo_command: "install -d -m 0755 /etc/apt/keyrings && curl -fsSL {key} | gpg --dearmor --yes -o /etc/apt/keyrings/{name}.gpg && echo 'deb [signed-by=/etc/apt/keyrings/'{name}'.gpg]' {url} {components} > /etc/apt/sources.list.d/{name}.list && apt update",
+        remove_repo_command: "rm -f /etc/apt/sources.list.d/{name}.list /etc/apt/sources.list.d/{name}.sources /etc/apt/keyrings/{name}.gpg && apt update",
+        repo_files: &[
+            "/etc/apt/sources.list.d/{name}.list",
+            "/etc/apt/sources.list.d/{name}.sources",
+            "/etc/apt/keyrings/{name}.gpg",
+        ],
+        deps_command: "apt-cache depends --no-recommends --no-suggests --no-conflicts --no-breaks --no-replaces --no-enhances {} 2>/dev/null | awk '$1 ~ /Depends:$/ { print $2 }' | tr -d '<>' | sort -u",
+        rdeps_command: "apt-cache rdepends --installed --no-recommends --no-suggests --no-conflicts --no-breaks --no-replaces --no-enhances {} 2>/dev/null | tail -n +3 | tr -d ' |' | sort -u",
+        simulate_key: "-s",
+        simulate_upgrade_command: "",
+        list_base_command: "dpkg-query -Wf '${Package} ${Essential} ${Priority}\\n' 2>/dev/null | awk '$2 == \"yes\" || $3 == \"required\" || $3 == \"important\" { print $1 }'",
+    }
+}
+
+/// Generates an instance of 'apk'.
+fn get_apk() -> PackageManager {
+    PackageManager {
+        command_name: "apk",
+        search_key: "search -q",
+        install_key: "add",
+        uninstall_key: "del",
+        run_key: "",
+        no_confirm_key: "",
+        does_need_root: true,
+        local_install_key: "add --allow-untrusted",
+        file_extensions: &[".apk"],
+        distributions: &["alpine"],
+        does_need_reboot: false,
+        apply_live_key: "",
+        root_key: "--root",
+        list_explicit_command: "sed 's/[<>=~@].*//' /etc/apk/world",
+        list_dependencies_command: "",
+        info_key: "",
+        info_fields: &[],
+        installed_version_command: "",
+        refresh_key: "update",
+        upgrade_key: "upgrade",
+        upgrade_package_key: "upgrade",
+        outdated_command: "apk version -l '<' | tail -n +2",
+        outdated_format: UpdateFormat::ApkVersion,
+        provides_command: "",
+        owner_command: "apk info -W {} 2>/dev/null | sed -n 's/.* is owned by //p'",
+        install_version_command: "apk add {}",
+        pin_format: "{name}={version}",
+        does_order_versions: true,
+        remote_command: "",
+        undo_transaction_command: "",
+        last_transaction_command: "",
+        versions_command: "apk policy {} 2>/dev/null | sed -nE 's/^  ([^ ]+):$/\\1/p'",
+        hold_command: "",
+        unhold_command: "",
+        list_held_command: "",
+        list_orphans_command: "",
+        autoremove_command: "",
+        list_cache_command: "du -sb /var/cache/apk 2>/dev/null | awk '{ print $2, $1 }'",
+        clean_command: "apk cache clean",
+        list_repos_command: "",
+        hosted_repo_prefix: "",
+        add_hosted_repo_command: "",
+        remove_hosted_repo_command: "",
+        repo_extension: "",
+        add_repo_command: "",
+        remove_repo_command: "",
+        repo_files: &[],
+        deps_command: "apk info -R {} 2>/dev/null | tail -n +2",
+        rdeps_command: "apk info -r {} 2>/dev/null | tail -n +2",
+        simulate_key: "--simulate",
+        simulate_upgrade_command: "",
+        list_base_command:
+            "echo alpine-base; apk info -qR alpine-base 2>/dev/null | sed 's/[<>=~].*//'",
     }
 }
 
@@ -127,6 +992,58 @@ fn get_snap() -> PackageManager {
         run_key: "",
         no_confirm_key: "",
         does_need_root: false,
+        local_install_key: "install --dangerous",
+        file_extensions: &[".snap"],
+        distributions: &[],
+        does_need_reboot: false,
+        apply_live_key: "",
+        root_key: "",
+        list_explicit_command: "snap list | awk 'NR > 1 && $NF !~ /base|core|snapd/ { print $1 }'",
+        list_dependencies_command:
+            "snap list | awk 'NR > 1 && $NF ~ /base|core|snapd/ { print $1 }'",
+        info_key: "info",
+        info_fields: &[
+            ("License", "license"),
+            ("Homepage", "store-url"),
+            ("Repository", "publisher"),
+        ],
+        installed_version_command: "snap list {} 2>/dev/null | awk 'NR == 2 { print $2 }'",
+        refresh_key: "",
+        upgrade_key: "refresh",
+        upgrade_package_key: "refresh",
+        outdated_command: "snap refresh --list 2>/dev/null | tail -n +2",
+        outdated_format: UpdateFormat::Columns,
+        provides_command: "",
+        owner_command:
+            "echo {} | sed -nE 's#^/snap/bin/([^./]+).*#\\1#p; t; s#^/snap/([^/]+)/.*#\\1#p'",
+        install_version_command: "snap install {name} --channel={version}",
+        pin_format: "",
+        does_order_versions: false,
+        remote_command: "",
+        undo_transaction_command: "",
+        last_transaction_command: "",
+        versions_command:
+            "snap info {} 2>/dev/null | sed -nE 's#^  ([^ /]+/[^ :]+): +[0-9A-Za-z].*#\\1#p'",
+        hold_command: "snap refresh --hold {}",
+        unhold_command: "snap refresh --unhold {}",
+        list_held_command: "snap list 2>/dev/null | awk 'NR > 1 && $NF ~ /held/ { print $1 }'",
+        list_orphans_command: "",
+        autoremove_command: "",
+        list_cache_command: "snap list --all 2>/dev/null | awk '/disabled/ { print \"/var/lib/snapd/snaps/\" $1 \"_\" $3 \".snap\" }' | xargs -r du -b 2>/dev/null | awk '{ print $2, $1 }'",
+        clean_command: "snap list --all | awk '/disabled/ { print $1, $3 }' | while read -r name revision; do snap remove \"$name\" --revision=\"$revision\"; done",
+        list_repos_command: "",
+        hosted_repo_prefix: "",
+        add_hosted_repo_command: "",
+        remove_hosted_repo_command: "",
+        repo_extension: "",
+        add_repo_command: "",
+        remove_repo_command: "",
+        repo_files: &[],
+        deps_command: "",
+        rdeps_command: "",
+        simulate_key: "",
+        simulate_upgrade_command: "",
+        list_base_command: "snap list 2>/dev/null | awk 'NR > 1 && $6 ~ /base|core|snapd/ { print $1 }'",
     }
 }
 
@@ -140,6 +1057,57 @@ fn get_flatpak() -> PackageManager {
         run_key: "run",
         no_confirm_key: "",
         does_need_root: false,
+        local_install_key: "install --from",
+        file_extensions: &[".flatpakref"],
+        distributions: &[],
+        does_need_reboot: false,
+        apply_live_key: "",
+        root_key: "",
+        list_explicit_command: "flatpak list --app --columns=application",
+        list_dependencies_command: "flatpak list --runtime --columns=application",
+        info_key: "remote-info {remote}",
+        info_fields: &[
+            ("Version", "Version"),
+            ("Size", "Installed"),
+            ("License", "License"),
+            ("Dependencies", "Runtime"),
+            ("Repository", "Origin"),
+        ],
+        installed_version_command: "flatpak info {} 2>/dev/null | awk '$1 == \"Version:\" { v = $2 } END { if (NR) print (v ? v : \"unknown\") }'",
+        refresh_key: "",
+        upgrade_key: "update",
+        upgrade_package_key: "update",
+        outdated_command: "flatpak remote-ls --updates --columns=application,version",
+        outdated_format: UpdateFormat::Columns,
+        provides_command: "",
+        owner_command: "echo {} | sed -nE 's#^.*/flatpak/(app|runtime)/([^/]+)/.*#\\2#p; t; s#^.*/flatpak/exports/bin/([^/]+)$#\\1#p'",
+        install_version_command: "flatpak install -y {remote} {name} && flatpak update -y --commit={version} {name}",
+        pin_format: "",
+        does_order_versions: false,
+        remote_command: "flatpak search --columns=application,remotes {} 2>/dev/null",
+        undo_transaction_command: "",
+        last_transaction_command: "",
+        versions_command: "flatpak remote-info --log {remote} {} 2>/dev/null | awk '$1 == \"Commit:\" { print $2 }'",
+        hold_command: "flatpak mask {}",
+        unhold_command: "flatpak mask --remove {}",
+        list_held_command: "flatpak mask 2>/dev/null | awk '{ print $1 }'",
+        list_orphans_command: "yes n | flatpak uninstall --unused 2>/dev/null | awk '$1 ~ /^[0-9]+\\.$/ { print $2 }'",
+        autoremove_command: "flatpak uninstall --unused -y",
+        list_cache_command: "",
+        clean_command: "",
+        list_repos_command: "flatpak remotes --columns=name,url",
+        hosted_repo_prefix: "",
+        add_hosted_repo_command: "",
+        remove_hosted_repo_command: "",
+        repo_extension: ".flatpakrepo",
+        add_repo_command: "flatpak remote-add --if-not-exists {name} {url}",
+        remove_repo_command: "flatpak remote-delete {name}",
+        repo_files: &[],
+        deps_command: "",
+        rdeps_command: "",
+        simulate_key: "",
+        simulate_upgrade_command: "",
+        list_base_command: "",
     }
 }
 
@@ -153,6 +1121,52 @@ fn get_brew() -> PackageManager {
         run_key: "",
         no_confirm_key: "",
         does_need_root: false,
+        local_install_key: "",
+        file_extensions: &[],
+        distributions: &[],
+        does_need_reboot: false,
+        apply_live_key: "",
+        root_key: "",
+        list_explicit_command: "brew leaves; brew list --cask -1",
+        list_dependencies_command: "brew list --formula -1 | grep -vxF -f <(brew leaves)",
+        info_key: "info",
+        info_fields: &[("License", "License"), ("Repository", "From")],
+        installed_version_command: "brew list --versions {} 2>/dev/null | awk '{ print $2 }'",
+        refresh_key: "update",
+        upgrade_key: "upgrade",
+        upgrade_package_key: "upgrade",
+        outdated_command: "brew outdated --verbose",
+        outdated_format: UpdateFormat::Brew,
+        provides_command: "",
+        owner_command: "readlink -f {} | sed -nE 's#^.*/(Cellar|Caskroom)/([^/]+)/.*#\\2#p'",
+        install_version_command: "brew install {}",
+        pin_format: "{name}@{version}",
+        does_order_versions: true,
+        remote_command: "",
+        undo_transaction_command: "",
+        last_transaction_command: "",
+        versions_command:
+            "brew search --formula {} 2>/dev/null | sed -nE \"s/^\"{}\"@(.+)$/\\1/p\" | sort -ruV",
+        hold_command: "brew pin {}",
+        unhold_command: "brew unpin {}",
+        list_held_command: "brew list --pinned",
+        list_orphans_command: "brew autoremove --dry-run 2>/dev/null | tail -n +2",
+        autoremove_command: "brew autoremove",
+        list_cache_command: "du -sb \"$(brew --cache)\" 2>/dev/null | awk '{ print $2, $1 }'",
+        clean_command: "brew cleanup --prune=all",
+        list_repos_command: "brew tap",
+        hosted_repo_prefix: "",
+        add_hosted_repo_command: "brew tap {}",
+        remove_hosted_repo_command: "brew untap {}",
+        repo_extension: "",
+        add_repo_command: "",
+        remove_repo_command: "",
+        repo_files: &[],
+        deps_command: "brew deps --direct {}",
+        rdeps_command: "brew uses --installed {}",
+        simulate_key: "",
+        simulate_upgrade_command: "",
+        list_base_command: "",
     }
 }
 
@@ -162,9 +1176,11 @@ pub fn get_known_package_managers_for_linux() -> Vec<PackageManager> {
         get_pacman(),
         get_paru(),
         get_dnf(),
+        get_rpm_ostree(),
         get_apt(),
-        get_snap(),
+        get_apk(),
         get_flatpak(),
+        get_snap(),
     ]
 }
 
@@ -186,6 +1202,51 @@ mod tests {
             run_key: "start",
             no_confirm_key: "-y",
             does_need_root: true,
+            local_install_key: "add-file",
+            file_extensions: &[".pkg", ".pkg.tar"],
+            distributions: &["pack-os"],
+            does_need_reboot: false,
+            apply_live_key: "",
+            root_key: "--root",
+            list_explicit_command: "pack-man list --explicit",
+            list_dependencies_command: "pack-man list --deps",
+            info_key: "show",
+            info_fields: &[("Version", "Version"), ("License", "Licence")],
+            installed_version_command: "pack-man version {}",
+            refresh_key: "sync",
+            upgrade_key: "upgrade",
+            upgrade_package_key: "upgrade",
+            outdated_command: "pack-man outdated",
+            outdated_format: UpdateFormat::Arrow,
+            provides_command: "pack-man provides {}",
+            owner_command: "pack-man owner {}",
+            install_version_command: "pack-man add {}",
+            pin_format: "{name}@{version}",
+            does_order_versions: true,
+            remote_command: "pack-man remote {}",
+            undo_transaction_command: "pack-man undo {}",
+            last_transaction_command: "pack-man last-transaction",
+            versions_command: "pack-man versions {}",
+            hold_command: "pack-man hold {}",
+            unhold_command: "pack-man unhold {}",
+            list_held_command: "pack-man held",
+            list_orphans_command: "pack-man orphans",
+            autoremove_command: "pack-man autoremove",
+            list_cache_command: "pack-man cache",
+            clean_command: "pack-man clean",
+            list_repos_command: "pack-man repos",
+            hosted_repo_prefix: "pm:",
+            add_hosted_repo_command: "pack-man enable {}",
+            remove_hosted_repo_command: "pack-man disable {}",
+            repo_extension: ".repo",
+            add_repo_command: "pack-man add-repo {name} {url} {components} {key}",
+            remove_repo_command: "pack-man remove-repo {name}",
+            repo_files: &["/etc/pack-man/{name}.repo"],
+            deps_command: "pack-man deps {}",
+            rdeps_command: "pack-man rdeps {}",
+            simulate_key: "--simulate",
+            simulate_upgrade_command: "pack-man outdated",
+            list_base_command: "pack-man base",
         }
     }
 
@@ -198,6 +1259,51 @@ mod tests {
             run_key: "",
             no_confirm_key: "-y",
             does_need_root: false,
+            local_install_key: "",
+            file_extensions: &[],
+            distributions: &[],
+            does_need_reboot: false,
+            apply_live_key: "",
+            root_key: "",
+            list_explicit_command: "pack-man list",
+            list_dependencies_command: "",
+            info_key: "",
+            info_fields: &[],
+            installed_version_command: "",
+            refresh_key: "",
+            upgrade_key: "",
+            upgrade_package_key: "",
+            outdated_command: "",
+            outdated_format: UpdateFormat::Columns,
+            provides_command: "",
+            owner_command: "",
+            install_version_command: "",
+            pin_format: "",
+            does_order_versions: false,
+            remote_command: "",
+            undo_transaction_command: "",
+            last_transaction_command: "",
+            versions_command: "",
+            hold_command: "",
+            unhold_command: "",
+            list_held_command: "",
+            list_orphans_command: "",
+            autoremove_command: "",
+            list_cache_command: "",
+            clean_command: "",
+            list_repos_command: "",
+            hosted_repo_prefix: "",
+            add_hosted_repo_command: "",
+            remove_hosted_repo_command: "",
+            repo_extension: "",
+            add_repo_command: "",
+            remove_repo_command: "",
+            repo_files: &[],
+            deps_command: "",
+            rdeps_command: "",
+            simulate_key: "",
+            simulate_upgrade_command: "",
+            list_base_command: "",
         }
     }
 
@@ -213,39 +1319,569 @@ mod tests {
     #[test]
     fn gen_install_command_with_root() {
         assert_eq!(
-            String::from("sudo pack-man add emacs -y"),
+            String::from("pack-man add 'emacs' -y"),
             get_package_manager_with_root().gen_install_command("emacs"),
-            "Generates install command for a package manager with root"
+            "Generates install command without elevating privileges for a package manager with root"
         );
     }
 
     #[test]
     fn gen_install_command_without_root() {
         assert_eq!(
-            String::from("pack-man add emacs -y"),
+            String::from("pack-man add 'emacs' -y"),
             get_package_manager_without_root().gen_install_command("emacs"),
             "Generates install command for a package manager without root"
         );
     }
 
+    #[test]
+    fn gen_local_install_command_with_root() {
+        assert_eq!(
+            String::from("pack-man add-file '/tmp/emacs.pkg' -y"),
+            get_package_manager_with_root().gen_local_install_command("/tmp/emacs.pkg"),
+            "Generates local install command for a package manager with root"
+        );
+        assert_eq!(
+            String::from("pack-man add-file '/tmp/my emacs'\\''s.pkg' -y"),
+            get_package_manager_with_root().gen_local_install_command("/tmp/my emacs's.pkg"),
+            "Quotes a path with characters special to shells"
+        );
+    }
+
+    #[test]
+    fn can_install_file_with_known_extension() {
+        assert!(
+            get_package_manager_with_root().can_install_file("./emacs-27.1.PKG.tar"),
+            "Accepts a file with a known extension"
+        );
+    }
+
+    #[test]
+    fn can_install_file_with_unknown_extension() {
+        assert!(
+            !get_package_manager_with_root().can_install_file("./emacs-27.1.deb"),
+            "Rejects a file with an unknown extension"
+        );
+    }
+
+    #[test]
+    fn get_file_package_name_some_values() {
+        assert_eq!(
+            Some(String::from("emacs-nox")),
+            get_package_manager_with_root().get_file_package_name("/tmp/emacs-nox-27.1-1.PKG.tar"),
+            "Gets the name before the version of a package file"
+        );
+        assert_eq!(
+            Some(String::from("emacs")),
+            get_package_manager_with_root().get_file_package_name("./emacs_27.1_amd64.pkg"),
+            "Gets the name before an underscore in a package file"
+        );
+        assert_eq!(
+            None,
+            get_package_manager_with_root().get_file_package_name("./emacs.deb"),
+            "Gets no name from a file with an unknown extension"
+        );
+    }
+
+    #[test]
+    fn can_install_file_without_local_install_key() {
+        assert!(
+            !get_package_manager_without_root().can_install_file("./emacs.pkg"),
+            "Rejects a file when the package manager cannot install local files"
+        );
+    }
+
+    #[test]
+    fn get_hosted_repo_with_prefix() {
+        assert_eq!(
+            Some("owner/name"),
+            get_package_manager_with_root().get_hosted_repo("pm:owner/name"),
+            "Gets the repository within a hosted one with the prefix of the package manager"
+        );
+        assert_eq!(
+            None,
+            get_package_manager_with_root().get_hosted_repo("ppa:owner/name"),
+            "Rejects a hosted repository with the prefix of another package manager"
+        );
+        assert_eq!(
+            None,
+            get_package_manager_without_root().get_hosted_repo("owner/name"),
+            "Rejects a hosted repository when the package manager cannot add one"
+        );
+    }
+
+    #[test]
+    fn can_add_repo_file_with_known_extension() {
+        assert!(
+            get_package_manager_with_root().can_add_repo_file("https://example.com/emacs.repo"),
+            "Accepts a repository file with a known extension"
+        );
+        assert!(
+            !get_package_manager_with_root().can_add_repo_file("https://example.com/emacs"),
+            "Rejects a URL without a known extension"
+        );
+    }
+
+    #[test]
+    fn get_repo_files_for_command() {
+        let apt = get_apt();
+
+        assert_eq!(
+            vec![
+                String::from("/etc/apt/sources.list.d/docker.list"),
+                String::from("/etc/apt/keyrings/docker.gpg"),
+            ],
+            apt.get_repo_files(apt.add_repo_command, "docker"),
+            "Gets the files that adding a repository writes"
+        );
+        assert_eq!(
+            vec![
+                String::from("/etc/apt/sources.list.d/docker.list"),
+                String::from("/etc/apt/sources.list.d/docker.sources"),
+                String::from("/etc/apt/keyrings/docker.gpg"),
+            ],
+            apt.get_repo_files(apt.remove_repo_command, "docker"),
+            "Gets the files that removing a repository deletes, in either format"
+        );
+    }
+
+    #[test]
+    fn gen_simulated_command_some_values() {
+        assert_eq!(
+            Some(String::from("pack-man install emacs --simulate")),
+            get_package_manager_with_root().gen_simulated_command("sudo pack-man install emacs -y"),
+            "Replaces the option to skip confirmation with the one to simulate, without elevating"
+        );
+        assert_eq!(
+            Some(String::from("pack-man outdated")),
+            get_package_manager_with_root().gen_simulated_command("sudo pack-man upgrade -y"),
+            "Previews an upgrade of every package through its own command"
+        );
+        assert_eq!(
+            None,
+            get_package_manager_with_root()
+                .gen_simulated_command("pack-man orphans | pack-man remove -"),
+            "Does not simulate piped commands"
+        );
+        assert_eq!(
+            None,
+            get_package_manager_without_root().gen_simulated_command("pack-man install emacs"),
+            "Does not simulate through a package manager that cannot"
+        );
+    }
+
+    #[test]
+    fn gen_simulated_command_pacman() {
+        assert_eq!(
+            Some(String::from("pacman -Qu")),
+            get_pacman().gen_simulated_command("sudo pacman -Syu --noconfirm"),
+            "Lists pending upgrades instead of syncing package databases to simulate an upgrade"
+        );
+        assert_eq!(
+            Some(String::from("pacman -S emacs --print")),
+            get_pacman().gen_simulated_command("sudo pacman -S emacs --noconfirm"),
+            "Simulates installing a package"
+        );
+        assert_eq!(
+            None,
+            get_pacman().gen_simulated_command("sudo pacman -Sy emacs --noconfirm"),
+            "Does not simulate a command that syncs package databases"
+        );
+    }
+
+    #[test]
+    fn get_list_commands_any() {
+        assert_eq!(
+            vec!["pack-man list --explicit", "pack-man list --deps"],
+            get_package_manager_with_root().get_list_commands(&InstallReason::Any),
+            "Gets commands to list all installed packages"
+        );
+    }
+
+    #[test]
+    fn get_list_commands_unsupported() {
+        assert_eq!(
+            Vec::<&str>::new(),
+            get_package_manager_without_root().get_list_commands(&InstallReason::Dependency),
+            "Gets no commands to list packages for an unsupported reason"
+        );
+    }
+
+    #[test]
+    fn gen_install_packages_command_special_characters() {
+        assert_eq!(
+            String::from("pack-man add 'git; rm -rf ~' 'my emacs' -y"),
+            get_package_manager_with_root()
+                .gen_install_packages_command(&["git; rm -rf ~", "my emacs"]),
+            "Quotes package names with characters special to shells"
+        );
+        assert_eq!(
+            String::from("pack-man remove 'git; rm -rf ~' -y"),
+            get_package_manager_with_root().gen_uninstall_command("git; rm -rf ~"),
+            "Quotes a package name to uninstall with characters special to shells"
+        );
+    }
+
+    #[test]
+    fn gen_uninstall_command_native() {
+        assert_eq!(
+            String::from("apt remove 'emacs' -y"),
+            get_apt().gen_uninstall_command("emacs"),
+            "Generates an uninstall command that apt accepts"
+        );
+        assert_eq!(
+            String::from("dnf remove 'emacs' -y"),
+            get_dnf().gen_uninstall_command("emacs"),
+            "Generates an uninstall command that dnf accepts"
+        );
+    }
+
+    #[test]
+    fn gen_install_packages_command_some_values() {
+        assert_eq!(
+            String::from("pack-man add 'emacs' 'vim' -y"),
+            get_package_manager_with_root().gen_install_packages_command(&["emacs", "vim"]),
+            "Generates a single install command for multiple packages"
+        );
+    }
+
     #[test]
     fn gen_uninstall_command_with_root() {
         assert_eq!(
-            String::from("sudo pack-man remove emacs -y"),
+            String::from("pack-man remove 'emacs' -y"),
             get_package_manager_with_root().gen_uninstall_command("emacs"),
-            "Generates uninstall command for a package manager with root"
+            "Generates uninstall command without elevating privileges for a package manager with root"
         );
     }
 
     #[test]
     fn gen_uninstall_command_without_root() {
         assert_eq!(
-            String::from("pack-man remove emacs -y"),
+            String::from("pack-man remove 'emacs' -y"),
             get_package_manager_without_root().gen_uninstall_command("emacs"),
             "Generates uninstall command for a package manager without root"
         );
     }
 
+    #[test]
+    fn gen_uninstall_packages_command_some_values() {
+        assert_eq!(
+            String::from("pack-man remove 'emacs' 'vim' -y"),
+            get_package_manager_without_root().gen_uninstall_packages_command(&["emacs", "vim"]),
+            "Generates a single uninstall command for multiple packages"
+        );
+    }
+
+    #[test]
+    fn gen_rooted_command_with_root() {
+        assert_eq!(
+            Ok(String::from("pack-man --root='/mnt' add emacs -y")),
+            get_package_manager_with_root().gen_rooted_command("pack-man add emacs -y", "/mnt"),
+            "Generates a command that targets an alternate root"
+        );
+        assert_eq!(
+            Ok(String::from(
+                "pack-man --root='/my root' add emacs -y || pack-man --root='/my root' downgrade emacs -y"
+            )),
+            get_package_manager_with_root().gen_rooted_command(
+                "pack-man add emacs -y || pack-man downgrade emacs -y",
+                "/my root"
+            ),
+            "Generates chained commands that all target an alternate root"
+        );
+    }
+
+    #[test]
+    fn gen_rooted_command_without_root() {
+        assert!(
+            get_package_manager_without_root()
+                .gen_rooted_command("pack-man add emacs", "/mnt")
+                .is_err(),
+            "Fails to target an alternate root without a way to"
+        );
+        assert!(
+            get_package_manager_with_root()
+                .gen_rooted_command("sudo pack-man add emacs", "/mnt")
+                .is_err(),
+            "Fails to target an alternate root with a command that does not start with the package manager"
+        );
+    }
+
+    #[test]
+    fn gen_upgrade_command() {
+        assert_eq!(
+            String::from("pack-man upgrade -y"),
+            get_package_manager_with_root().gen_upgrade_command(),
+            "Generates command to upgrade all packages"
+        );
+    }
+
+    #[test]
+    fn gen_upgrade_package_command() {
+        assert_eq!(
+            String::from("pack-man upgrade emacs -y"),
+            get_package_manager_with_root().gen_upgrade_package_command("emacs"),
+            "Generates command to upgrade a package"
+        );
+    }
+
+    #[test]
+    fn parse_update_arrow() {
+        assert_eq!(
+            Some(Update {
+                name: String::from("emacs"),
+                current_version: String::from("29.1-1"),
+                new_version: String::from("29.1-2"),
+            }),
+            get_package_manager_with_root().parse_update("emacs 29.1-1 -> 29.1-2"),
+            "Parses an update with an arrow between versions"
+        );
+    }
+
+    #[test]
+    fn parse_update_apt_list() {
+        let mut package_manager = get_package_manager_with_root();
+        package_manager.outdated_format = UpdateFormat::AptList;
+
+        assert_eq!(
+            Some(Update {
+                name: String::from("curl"),
+                current_version: String::from("7.88.1-10"),
+                new_version: String::from("7.88.1-10+deb12u5"),
+            }),
+            package_manager.parse_update(
+                "curl/stable-security 7.88.1-10+deb12u5 amd64 [upgradable from: 7.88.1-10]"
+            ),
+            "Parses an update listed by apt"
+        );
+    }
+
+    #[test]
+    fn parse_update_apk_version() {
+        let mut package_manager = get_package_manager_with_root();
+        package_manager.outdated_format = UpdateFormat::ApkVersion;
+
+        assert_eq!(
+            Some(Update {
+                name: String::from("busybox-binsh"),
+                current_version: String::from("1.36.1-r2"),
+                new_version: String::from("1.36.1-r5"),
+            }),
+            package_manager.parse_update("busybox-binsh-1.36.1-r2    < 1.36.1-r5"),
+            "Parses an update listed by apk"
+        );
+    }
+
+    #[test]
+    fn parse_update_brew() {
+        let mut package_manager = get_package_manager_with_root();
+        package_manager.outdated_format = UpdateFormat::Brew;
+
+        assert_eq!(
+            Some(Update {
+                name: String::from("git"),
+                current_version: String::from("2.42.0"),
+                new_version: String::from("2.43.0"),
+            }),
+            package_manager.parse_update("git (2.42.0) < 2.43.0"),
+            "Parses an update listed by brew"
+        );
+    }
+
+    #[test]
+    fn parse_update_columns() {
+        let mut package_manager = get_package_manager_with_root();
+        package_manager.outdated_format = UpdateFormat::Columns;
+
+        assert_eq!(
+            Some(Update {
+                name: String::from("python3.11"),
+                current_version: String::new(),
+                new_version: String::from("3.11.7-1.fc39"),
+            }),
+            package_manager.parse_update("python3.11.x86_64    3.11.7-1.fc39    updates"),
+            "Parses an update without the current version"
+        );
+    }
+
+    #[test]
+    fn parse_update_not_an_update() {
+        assert_eq!(
+            None,
+            get_package_manager_with_root().parse_update("Listing... Done"),
+            "Parses no update from an unrelated line"
+        );
+    }
+
+    #[test]
+    fn gen_provides_command() {
+        assert_eq!(
+            String::from("pack-man provides '/usr/bin/emacs'"),
+            get_package_manager_with_root().gen_provides_command("/usr/bin/emacs"),
+            "Generates command to find packages that provide a file"
+        );
+    }
+
+    #[test]
+    fn gen_owner_command() {
+        assert_eq!(
+            String::from("pack-man owner '/usr/bin/emacs'"),
+            get_package_manager_with_root().gen_owner_command("/usr/bin/emacs"),
+            "Generates command to find the package that owns a file"
+        );
+    }
+
+    #[test]
+    fn gen_install_version_command() {
+        assert_eq!(
+            String::from("pack-man add 'emacs'@'29.1'"),
+            get_package_manager_with_root().gen_install_version_command("emacs", "29.1"),
+            "Generates command to install a particular version of a package"
+        );
+    }
+
+    #[test]
+    fn gen_info_command() {
+        assert_eq!(
+            String::from("pack-man show 'emacs'\\''s'"),
+            get_package_manager_with_root().gen_info_command("emacs's"),
+            "Generates command to show information about a package"
+        );
+    }
+
+    #[test]
+    fn gen_remote_command() {
+        assert_eq!(
+            String::from("pack-man remote 'emacs'"),
+            get_package_manager_with_root().gen_remote_command("emacs"),
+            "Generates command to find the remote that offers a package"
+        );
+    }
+
+    #[test]
+    fn gen_undo_transaction_command() {
+        assert_eq!(
+            String::from("pack-man undo '42'"),
+            get_package_manager_with_root().gen_undo_transaction_command("42"),
+            "Generates command to undo a transaction"
+        );
+    }
+
+    #[test]
+    fn gen_install_versions_command() {
+        assert_eq!(
+            String::from("pack-man add 'emacs'@'29.1' 'vim'@'9.0'"),
+            get_package_manager_with_root()
+                .gen_install_versions_command(&[("emacs", "29.1"), ("vim", "9.0")]),
+            "Generates command to install particular versions of packages in one go"
+        );
+        assert_eq!(
+            String::from("snap install 'emacs' --channel='latest/stable' && snap install 'vim' --channel='latest/edge'"),
+            get_snap().gen_install_versions_command(&[
+                ("emacs", "latest/stable"),
+                ("vim", "latest/edge")
+            ]),
+            "Chains commands that install particular versions of packages one at a time"
+        );
+    }
+
+    #[test]
+    fn gen_versions_command() {
+        assert_eq!(
+            String::from("pack-man versions 'emacs'"),
+            get_package_manager_with_root().gen_versions_command("emacs"),
+            "Generates command to list available versions of a package"
+        );
+    }
+
+    #[test]
+    fn gen_hold_command() {
+        assert_eq!(
+            String::from("pack-man hold 'emacs'"),
+            get_package_manager_with_root().gen_hold_command("emacs"),
+            "Generates command to hold a package against upgrades"
+        );
+    }
+
+    #[test]
+    fn gen_add_repo_command() {
+        assert_eq!(
+            String::from("pack-man add-repo 'emacs' 'https://example.com/emacs' 'stable main' 'https://example.com/key.gpg'"),
+            get_package_manager_with_root().gen_add_repo_command(
+                "emacs",
+                "https://example.com/emacs",
+                &["stable", "main"],
+                Some("https://example.com/key.gpg")
+            ),
+            "Generates command to add a repository at a URL"
+        );
+    }
+
+    #[test]
+    fn gen_remove_hosted_repo_command() {
+        assert_eq!(
+            String::from("pack-man disable 'owner/name'"),
+            get_package_manager_with_root().gen_remove_hosted_repo_command("owner/name"),
+            "Generates command to remove a hosted repository"
+        );
+    }
+
+    #[test]
+    fn gen_rdeps_command() {
+        assert_eq!(
+            String::from("pack-man rdeps 'emacs'"),
+            get_package_manager_with_root().gen_rdeps_command("emacs"),
+            "Generates command to list packages that depend on a package"
+        );
+    }
+
+    #[test]
+    fn gen_unhold_command() {
+        assert_eq!(
+            String::from("pack-man unhold 'emacs'"),
+            get_package_manager_with_root().gen_unhold_command("emacs"),
+            "Generates command to release a held package"
+        );
+    }
+
+    #[test]
+    fn gen_installed_version_command() {
+        assert_eq!(
+            String::from("pack-man version 'emacs'"),
+            get_package_manager_with_root().gen_installed_version_command("emacs"),
+            "Generates command to get the installed version of a package"
+        );
+    }
+
+    #[test]
+    fn parse_info_some_fields() {
+        assert_eq!(
+            vec![
+                ("Version", String::from("27.1-2")),
+                ("Size", String::new()),
+                ("License", String::from("GPL3")),
+                ("Homepage", String::new()),
+                ("Dependencies", String::new()),
+                ("Repository", String::new()),
+            ],
+            get_package_manager_with_root().parse_info(&[
+                String::from("Name     : emacs"),
+                String::from("Version  : 27.1-2"),
+                String::from("Licence  : GPL3"),
+            ]),
+            "Parses known fields from package information"
+        );
+    }
+
+    #[test]
+    fn parse_info_no_fields() {
+        assert_eq!(
+            Vec::<(&str, String)>::new(),
+            get_package_manager_with_root().parse_info(&[String::from("error: not found")]),
+            "Parses no fields when the package information is missing"
+        );
+    }
+
     #[test]
     fn gen_run_command_with_run_key() {
         assert_eq!(
@@ -1,5 +1,9 @@
 //! Contains logic around platform detection and package manager mappings.
 
+use std::fs;
+use std::path::Path;
+
+use crate::environment::{does_exist, run_command_and_get_list};
 use crate::package_managers::{
     get_known_package_managers_for_linux, get_known_package_managers_for_macos, PackageManager,
 };
@@ -12,6 +16,36 @@ pub enum Platform {
     Unknown,
 }
 
+/// Distributions and variants that ship an immutable base system.
+const IMMUTABLE_IDS: [&str; 9] = [
+    "silverblue",
+    "kinoite",
+    "sericea",
+    "onyx",
+    "coreos",
+    "iot",
+    "opensuse-microos",
+    "opensuse-aeon",
+    "opensuse-kalpa",
+];
+
+/// A Linux distribution as described by its os-release information.
+#[derive(Debug, PartialEq)]
+pub struct Distribution {
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub version_id: String,
+    pub name: String,
+    pub is_immutable: bool,
+}
+
+impl Distribution {
+    /// Returns whether the distribution is, or is derived from, the one with the supplied ID.
+    pub fn is_like(&self, id: &str) -> bool {
+        self.id == id || self.id_like.iter().any(|i| i == id)
+    }
+}
+
 /// Detects and returns current operating platform.
 pub fn get_operating_platform() -> Platform {
     if cfg!(target_os = "linux") {
@@ -25,12 +59,358 @@ pub fn get_operating_platform() -> Platform {
     }
 }
 
+/// Returns a readable name for the current operating platform.
+pub fn get_operating_platform_name() -> &'static str {
+    match get_operating_platform() {
+        Platform::Linux => "Linux",
+        Platform::MacOS => "MacOS",
+        // Platform::Windows => "Windows",
+        Platform::Unknown => "Unknown",
+    }
+}
+
+/// Detects the current Linux distribution.
+///
+/// Reads `/etc/os-release` (or `/usr/lib/os-release`) and falls back to `lsb_release` on systems
+/// that provide neither.
+pub fn get_distribution() -> Option<Distribution> {
+    if let Platform::Linux = get_operating_platform() {
+        ["/etc/os-release", "/usr/lib/os-release"]
+            .iter()
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .find_map(|contents| parse_os_release(&contents))
+            .or_else(get_distribution_from_lsb_release)
+            .map(|mut d| {
+                // rpm-ostree based systems mark themselves regardless of the variant
+                d.is_immutable = d.is_immutable || Path::new("/run/ostree-booted").exists();
+                d
+            })
+    } else {
+        None
+    }
+}
+
+/// Parses the contents of an os-release file into a distribution.
+///
+/// Returns `None` when the contents do not identify a distribution.
+pub fn parse_os_release(contents: &str) -> Option<Distribution> {
+    let mut id = String::new();
+    let mut id_like = Vec::<String>::new();
+    let mut version_id = String::new();
+    let mut name = String::new();
+    let mut pretty_name = String::new();
+    let mut variant_id = String::new();
+
+    contents
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| l.split_once('='))
+        .for_each(|(key, value)| {
+            let value = value.trim_matches(|c| c == '"' || c == '\'').to_string();
+
+            match key {
+                "ID" => id = value.to_lowercase(),
+                "ID_LIKE" => id_like = value.split_whitespace().map(|i| i.to_lowercase()).collect(),
+                "VERSION_ID" => version_id = value,
+                "NAME" => name = value,
+                "PRETTY_NAME" => pretty_name = value,
+                "VARIANT_ID" => variant_id = value.to_lowercase(),
+                _ => (),
+            }
+        });
+
+    if id.is_empty() {
+        return None;
+    }
+
+    Some(Distribution {
+        name: if pretty_name.is_empty() {
+            name
+        } else {
+            pretty_name
+        },
+        is_immutable: IMMUTABLE_IDS.contains(&id.as_str())
+            || IMMUTABLE_IDS.contains(&variant_id.as_str()),
+        id,
+        id_like,
+        version_id,
+    })
+}
+
+/// Detects the current distribution through `lsb_release`.
+fn get_distribution_from_lsb_release() -> Option<Distribution> {
+    if !does_exist("lsb_release") {
+        return None;
+    }
+
+    let id = run_command_and_get_list("lsb_release -si")[0].to_lowercase();
+
+    if id.is_empty() {
+        return None;
+    }
+
+    Some(Distribution {
+        id,
+        id_like: Vec::new(),
+        version_id: run_command_and_get_list("lsb_release -sr")[0].to_string(),
+        name: run_command_and_get_list("lsb_release -sd")[0]
+            .trim_matches('"')
+            .to_string(),
+        is_immutable: false,
+    })
+}
+
+/// Narrows down package managers to the ones that belong to the supplied distribution.
+///
+/// Package managers native to other distributions are left out, while universal ones are retained.
+/// When the distribution is unknown or none of the native package managers belong to it, all of
+/// the supplied package managers are retained.
+///
+/// On immutable distributions, only native package managers that layer packages onto the base
+/// system are retained, and universal package managers are preferred over them.
+pub fn filter_for_distribution(
+    package_managers: Vec<PackageManager>,
+    distribution: &Option<Distribution>,
+) -> Vec<PackageManager> {
+    let distribution = match distribution {
+        Some(d) => d,
+        None => return package_managers,
+    };
+
+    let is_relevant = |p: &PackageManager| {
+        p.distributions.iter().any(|d| distribution.is_like(d))
+            && p.does_need_reboot == distribution.is_immutable
+    };
+
+    if !package_managers.iter().any(is_relevant) && !distribution.is_immutable {
+        return package_managers;
+    }
+
+    let (universal_package_managers, native_package_managers): (Vec<_>, Vec<_>) = package_managers
+        .into_iter()
+        .filter(|p| p.distributions.is_empty() || is_relevant(p))
+        .partition(|p| p.distributions.is_empty());
+
+    if distribution.is_immutable {
+        universal_package_managers
+            .into_iter()
+            .chain(native_package_managers)
+            .collect()
+    } else {
+        native_package_managers
+            .into_iter()
+            .chain(universal_package_managers)
+            .collect()
+    }
+}
+
 /// Get relevant package managers for current operating system.
-pub fn get_relevant_package_managers() -> Option<Vec<PackageManager>> {
+pub fn get_relevant_package_managers(
+    distribution: &Option<Distribution>,
+) -> Option<Vec<PackageManager>> {
     match get_operating_platform() {
-        Platform::Linux => Some(get_known_package_managers_for_linux()),
+        Platform::Linux => Some(filter_for_distribution(
+            get_known_package_managers_for_linux(),
+            distribution,
+        )),
         Platform::MacOS => Some(get_known_package_managers_for_macos()),
         // Platform::Windows => (),
         _ => None,
     }
 }
+
+/// Returns the native package manager from the supplied ones, the first one that exists.
+pub fn get_native_package_manager(package_managers: &[PackageManager]) -> Option<&PackageManager> {
+    package_managers
+        .iter()
+        .find(|p| !p.distributions.is_empty() && does_exist(p.command_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_command_names(package_managers: &[PackageManager]) -> Vec<&str> {
+        package_managers
+            .iter()
+            .map(|p| p.command_name)
+            .collect::<Vec<&str>>()
+    }
+
+    #[test]
+    fn parse_os_release_arch() {
+        assert_eq!(
+            Some(Distribution {
+                id: String::from("arch"),
+                id_like: Vec::new(),
+                version_id: String::new(),
+                name: String::from("Arch Linux"),
+                is_immutable: false,
+            }),
+            parse_os_release(include_str!("../tests/fixtures/os-release/arch")),
+            "Parses a rolling distribution without a version"
+        );
+    }
+
+    #[test]
+    fn parse_os_release_linuxmint() {
+        assert_eq!(
+            Some(Distribution {
+                id: String::from("linuxmint"),
+                id_like: vec![String::from("ubuntu"), String::from("debian")],
+                version_id: String::from("21.2"),
+                name: String::from("Linux Mint 21.2"),
+                is_immutable: false,
+            }),
+            parse_os_release(include_str!("../tests/fixtures/os-release/linuxmint")),
+            "Parses a derived distribution with multiple parents"
+        );
+    }
+
+    #[test]
+    fn parse_os_release_fedora() {
+        assert_eq!(
+            Some(Distribution {
+                id: String::from("fedora"),
+                id_like: Vec::new(),
+                version_id: String::from("39"),
+                name: String::from("Fedora Linux 39 (Workstation Edition)"),
+                is_immutable: false,
+            }),
+            parse_os_release(include_str!("../tests/fixtures/os-release/fedora")),
+            "Parses an unquoted version"
+        );
+    }
+
+    #[test]
+    fn parse_os_release_rocky() {
+        assert_eq!(
+            Some(Distribution {
+                id: String::from("rocky"),
+                id_like: vec![
+                    String::from("rhel"),
+                    String::from("centos"),
+                    String::from("fedora")
+                ],
+                version_id: String::from("9.2"),
+                name: String::from("Rocky Linux 9.2 (Blue Onyx)"),
+                is_immutable: false,
+            }),
+            parse_os_release(include_str!("../tests/fixtures/os-release/rocky")),
+            "Parses single-quoted values and skips comments"
+        );
+    }
+
+    #[test]
+    fn parse_os_release_silverblue() {
+        assert!(
+            parse_os_release(include_str!("../tests/fixtures/os-release/silverblue"))
+                .unwrap()
+                .is_immutable,
+            "Detects an immutable variant of a distribution"
+        );
+    }
+
+    #[test]
+    fn parse_os_release_microos() {
+        assert!(
+            parse_os_release(include_str!("../tests/fixtures/os-release/microos"))
+                .unwrap()
+                .is_immutable,
+            "Detects an immutable distribution"
+        );
+    }
+
+    #[test]
+    fn parse_os_release_without_id() {
+        assert_eq!(
+            None,
+            parse_os_release("NAME=\"Mystery Linux\"\nVERSION_ID=1"),
+            "Does not identify a distribution without an ID"
+        );
+    }
+
+    #[test]
+    fn filter_for_distribution_derived() {
+        let distribution = parse_os_release(include_str!("../tests/fixtures/os-release/ubuntu"));
+
+        assert_eq!(
+            vec!["apt", "flatpak", "snap"],
+            get_command_names(&filter_for_distribution(
+                get_known_package_managers_for_linux(),
+                &distribution
+            )),
+            "Retains the native package manager of the parent distribution"
+        );
+    }
+
+    #[test]
+    fn filter_for_distribution_alpine() {
+        let distribution = parse_os_release(include_str!("../tests/fixtures/os-release/alpine"));
+
+        assert_eq!(
+            vec!["apk", "flatpak", "snap"],
+            get_command_names(&filter_for_distribution(
+                get_known_package_managers_for_linux(),
+                &distribution
+            )),
+            "Retains the native package manager of the distribution"
+        );
+    }
+
+    #[test]
+    fn filter_for_distribution_fedora() {
+        let distribution = parse_os_release(include_str!("../tests/fixtures/os-release/fedora"));
+
+        assert_eq!(
+            vec!["dnf", "flatpak", "snap"],
+            get_command_names(&filter_for_distribution(
+                get_known_package_managers_for_linux(),
+                &distribution
+            )),
+            "Retains the package manager that installs onto a mutable system"
+        );
+    }
+
+    #[test]
+    fn filter_for_distribution_silverblue() {
+        let distribution =
+            parse_os_release(include_str!("../tests/fixtures/os-release/silverblue"));
+
+        assert_eq!(
+            vec!["flatpak", "snap", "rpm-ostree"],
+            get_command_names(&filter_for_distribution(
+                get_known_package_managers_for_linux(),
+                &distribution
+            )),
+            "Prefers universal package managers over layering on an immutable system"
+        );
+    }
+
+    #[test]
+    fn filter_for_distribution_microos() {
+        let distribution = parse_os_release(include_str!("../tests/fixtures/os-release/microos"));
+
+        assert_eq!(
+            vec!["flatpak", "snap"],
+            get_command_names(&filter_for_distribution(
+                get_known_package_managers_for_linux(),
+                &distribution
+            )),
+            "Retains only universal package managers on an immutable system without layering"
+        );
+    }
+
+    #[test]
+    fn filter_for_distribution_unknown() {
+        assert_eq!(
+            get_command_names(&get_known_package_managers_for_linux()),
+            get_command_names(&filter_for_distribution(
+                get_known_package_managers_for_linux(),
+                &parse_os_release("ID=mystery")
+            )),
+            "Retains all package managers for an unknown distribution"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Contains logic around elevating privileges for commands that need root.
+
+use std::env;
+
+use crate::environment::{does_exist, quote, run_command_and_get_list};
+
+/// Tools that can run commands as root, in the default order of preference.
+const ESCALATION_TOOLS: [&str; 4] = ["sudo", "doas", "run0", "pkexec"];
+
+/// The environment variable to override the order of preference of escalation tools.
+pub const ESCALATION_TOOLS_VARIABLE: &str = "FORAGET_ESCALATION";
+
+/// An enumeration of ways to run commands that need root.
+#[derive(Debug, PartialEq)]
+pub enum Privilege {
+    Root,
+    Tool(String),
+    Unavailable(Vec<String>),
+}
+
+impl Privilege {
+    /// Generates a command that runs with root privileges.
+    ///
+    /// Commands chained with `&&`, `||` or `;`, or piped into one another, are run through a shell,
+    /// so that every one of them is elevated. Returns an error when there is no way to elevate privileges.
+    pub fn gen_command(&self, command: &str) -> Result<String, String> {
+        match self {
+            Privilege::Root => Ok(command.to_string()),
+            Privilege::Tool(tool) if ["&&", "|", ";"].iter().any(|o| command.contains(o)) => {
+                Ok(format!("{} sh -c {}", tool, quote(command)))
+            }
+            Privilege::Tool(tool) => Ok(format!("{} {}", tool, command)),
+            Privilege::Unavailable(tools) => Err(format!(
+                "Root privileges are needed but none of {} is available!",
+                tools.join(", ")
+            )),
+        }
+    }
+
+    /// Returns a readable description of the way privileges are elevated.
+    pub fn get_description(&self) -> String {
+        match self {
+            Privilege::Root => String::from("running as root"),
+            Privilege::Tool(tool) => tool.to_string(),
+            Privilege::Unavailable(_) => String::from("unavailable"),
+        }
+    }
+}
+
+/// Detects the way to run commands that need root.
+///
+/// No tool is needed when foraget already runs as root. Otherwise, the first available tool is
+/// picked in the order of preference, which can be overridden through `FORAGET_ESCALATION`. When
+/// none is available, the tools that were tried are kept to report them.
+pub fn get_privilege() -> Privilege {
+    if run_command_and_get_list("id -u")[0] == "0" {
+        return Privilege::Root;
+    }
+
+    let tools = get_preferred_tools(env::var(ESCALATION_TOOLS_VARIABLE).ok());
+    match tools.iter().find(|t| does_exist(t)) {
+        Some(tool) => Privilege::Tool(tool.to_string()),
+        None => Privilege::Unavailable(tools),
+    }
+}
+
+/// Gets escalation tools in the order of preference, from a comma or space separated list.
+///
+/// Falls back to the default order when no preference is supplied.
+fn get_preferred_tools(preference: Option<String>) -> Vec<String> {
+    let tools = preference
+        .unwrap_or_default()
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+        .collect::<Vec<String>>();
+
+    if tools.is_empty() {
+        ESCALATION_TOOLS.iter().map(|t| t.to_string()).collect()
+    } else {
+        tools
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gen_command_as_root() {
+        assert_eq!(
+            Ok(String::from("pacman -S emacs")),
+            Privilege::Root.gen_command("pacman -S emacs"),
+            "Does not elevate a command when running as root"
+        );
+    }
+
+    #[test]
+    fn gen_command_with_tool() {
+        assert_eq!(
+            Ok(String::from("doas pacman -S emacs")),
+            Privilege::Tool(String::from("doas")).gen_command("pacman -S emacs"),
+            "Elevates a command through a tool"
+        );
+    }
+
+    #[test]
+    fn gen_command_chained() {
+        assert_eq!(
+            Ok(String::from(
+                "sudo sh -c 'dnf install -y emacs || dnf downgrade -y emacs'"
+            )),
+            Privilege::Tool(String::from("sudo"))
+                .gen_command("dnf install -y emacs || dnf downgrade -y emacs"),
+            "Elevates every one of chained commands through a shell"
+        );
+    }
+
+    #[test]
+    fn gen_command_chained_with_quotes() {
+        assert_eq!(
+            Ok(String::from(
+                "sudo sh -c 'snap list --all | awk '\\''/disabled/ { print $1 }'\\'''"
+            )),
+            Privilege::Tool(String::from("sudo"))
+                .gen_command("snap list --all | awk '/disabled/ { print $1 }'"),
+            "Escapes single quotes in chained commands"
+        );
+    }
+
+    #[test]
+    fn gen_command_chained_with_quotes_round_trip() {
+        let command = Privilege::Tool(String::from("env"))
+            .gen_command("echo 'it'\\''s a b' | tr a x")
+            .unwrap();
+        let output = std::process::Command::new("/bin/bash")
+            .arg("-c")
+            .arg(&command)
+            .output()
+            .unwrap();
+
+        assert_eq!(
+            "it's x b\n",
+            String::from_utf8_lossy(&output.stdout),
+            "Runs chained commands with single quotes unchanged"
+        );
+    }
+
+    #[test]
+    fn gen_command_unavailable() {
+        assert_eq!(
+            Err(String::from(
+                "Root privileges are needed but none of doas, run0 is available!"
+            )),
+            Privilege::Unavailable(vec![String::from("doas"), String::from("run0")])
+                .gen_command("pacman -S emacs"),
+            "Fails to elevate a command without a tool, naming the tools that were tried"
+        );
+    }
+
+    #[test]
+    fn get_preferred_tools_default() {
+        assert_eq!(
+            vec!["sudo", "doas", "run0", "pkexec"],
+            get_preferred_tools(None),
+            "Uses the default order without a preference"
+        );
+    }
+
+    #[test]
+    fn get_preferred_tools_custom() {
+        assert_eq!(
+            vec!["doas", "run0"],
+            get_preferred_tools(Some(String::from("doas, run0"))),
+            "Uses the order from the preference"
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Contains logic around records of packages installed through foraget.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::environment::get_state_path;
+
+/// The file in the state directory with records of installed packages.
+pub const RECORDS_FILE: &str = "packages.toml";
+
+/// A package installed through foraget.
+///
+/// The name is the one the package was asked for by, while the identifier is the one the package
+/// manager knows it by, like `org.gnu.emacs` for `emacs` through Flatpak.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Record {
+    pub name: String,
+    pub backend: String,
+    pub identifier: String,
+}
+
+impl Record {
+    /// Creates a record for a package installed through a package manager.
+    pub fn new(name: &str, backend: &str, identifier: &str) -> Record {
+        Record {
+            name: name.to_string(),
+            backend: backend.to_string(),
+            identifier: identifier.to_string(),
+        }
+    }
+
+    /// Returns whether the record is for a package with the supplied name or identifier.
+    pub fn matches(&self, package: &str) -> bool {
+        self.name == package || self.identifier == package
+    }
+}
+
+/// Records of packages installed through foraget, as they are stored on disk.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Records {
+    #[serde(default)]
+    pub packages: Vec<Record>,
+}
+
+impl Records {
+    /// Gets the records for a package with the supplied name or identifier.
+    pub fn find(&self, package: &str) -> Vec<&Record> {
+        self.packages
+            .iter()
+            .filter(|r| r.matches(package))
+            .collect()
+    }
+
+    /// Adds a record, replacing one for the same package through the same package manager.
+    pub fn add(&mut self, record: Record) {
+        self.remove(&record.backend, &record.identifier);
+        self.packages.push(record);
+    }
+
+    /// Removes the record for a package through a package manager, if there is one.
+    pub fn remove(&mut self, backend: &str, identifier: &str) {
+        self.packages
+            .retain(|r| r.backend != backend || r.identifier != identifier);
+    }
+}
+
+/// Parses records from their TOML text.
+pub fn parse_records(text: &str) -> Result<Records, String> {
+    toml::from_str(text).map_err(|e| e.to_string())
+}
+
+/// Generates the TOML text of records.
+pub fn gen_records_text(records: &Records) -> Result<String, String> {
+    toml::to_string(records).map_err(|e| e.to_string())
+}
+
+/// Reads the records of installed packages from the state directory.
+///
+/// Returns no records when the file does not exist yet.
+pub fn read_records() -> Result<Records, String> {
+    match get_state_path(RECORDS_FILE) {
+        Some(path) if path.is_file() => parse_records(
+            &fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?,
+        )
+        .map_err(|e| format!("{}: {}", path.display(), e)),
+        _ => Ok(Records::default()),
+    }
+}
+
+/// Changes the records of installed packages in the state directory.
+pub fn update_records(change: impl FnOnce(&mut Records)) -> Result<(), String> {
+    let path = get_state_path(RECORDS_FILE)
+        .ok_or_else(|| String::from("The state directory could not be found."))?;
+
+    let mut records = read_records()?;
+    change(&mut records);
+    let text = gen_records_text(&records)?;
+
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_records() -> Records {
+        Records {
+            packages: vec![
+                Record::new("emacs", "flatpak", "org.gnu.emacs"),
+                Record::new("fd", "apt", "fd-find"),
+            ],
+        }
+    }
+
+    #[test]
+    fn gen_records_text_round_trip() {
+        assert_eq!(
+            Ok(get_records()),
+            parse_records(&gen_records_text(&get_records()).unwrap()),
+            "Parses back generated records"
+        );
+    }
+
+    #[test]
+    fn find_by_name_or_identifier() {
+        let records = get_records();
+
+        assert_eq!(
+            vec![&records.packages[0]],
+            records.find("emacs"),
+            "Finds a record by the name a package was asked for by"
+        );
+        assert_eq!(
+            vec![&records.packages[1]],
+            records.find("fd-find"),
+            "Finds a record by the identifier of a package"
+        );
+    }
+
+    #[test]
+    fn add_replacing() {
+        let mut records = get_records();
+        records.add(Record::new("gnu-emacs", "flatpak", "org.gnu.emacs"));
+
+        assert_eq!(
+            vec![
+                Record::new("fd", "apt", "fd-find"),
+                Record::new("gnu-emacs", "flatpak", "org.gnu.emacs"),
+            ],
+            records.packages,
+            "Replaces the record for the same package through the same package manager"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Contains logic around repositories that packages are installed from.
+
+/// A repository requested to be added.
+#[derive(Debug, PartialEq)]
+pub enum Repository {
+    /// A repository hosted on a service a package manager knows, like `ppa:owner/name`,
+    /// `copr:owner/project` or a Homebrew tap like `owner/name`.
+    Hosted(String),
+    /// A repository at a URL, along with the name to add it by, the suite and components that
+    /// follow the URL for APT, and the URL of the key it is signed with.
+    Remote {
+        name: String,
+        url: String,
+        components: Vec<String>,
+        key: Option<String>,
+    },
+}
+
+/// A repository configured for a package manager, as it is listed.
+#[derive(Debug, PartialEq)]
+pub struct ConfiguredRepository {
+    pub name: String,
+    pub url: String,
+}
+
+/// Parses a requested repository from its words, like `ppa:owner/name`, or a URL optionally
+/// followed by a suite and components.
+///
+/// A repository at a URL is named after it unless a name is supplied.
+pub fn parse_repository(
+    words: &[&str],
+    name: Option<&str>,
+    key: Option<&str>,
+) -> Result<Repository, String> {
+    let (first, rest) = match words.split_first() {
+        Some(w) => w,
+        None => return Err(String::from("No repository was supplied.")),
+    };
+
+    if !is_url(first) {
+        return match rest {
+            [] if first.contains('/') && is_valid_word(first) => {
+                Ok(Repository::Hosted(first.to_string()))
+            }
+            _ => Err(format!(
+                "'{}' is neither a URL nor a hosted repository like ppa:owner/name.",
+                words.join(" ")
+            )),
+        };
+    }
+
+    if let Some(c) = rest.iter().find(|c| !is_valid_word(c)) {
+        return Err(format!("The suite or component '{}' is invalid.", c));
+    }
+    if let Some(k) = key.filter(|k| !is_url(k)) {
+        return Err(format!("The key '{}' is not a URL.", k));
+    }
+
+    let name = match name {
+        Some(n) => n.to_string(),
+        None => derive_name(first)
+            .ok_or_else(|| format!("A name could not be made out of '{}', supply one.", first))?,
+    };
+    if !is_valid_name(&name) {
+        return Err(format!(
+            "The name '{}' is invalid, use letters, digits, '.', '_' and '-'.",
+            name
+        ));
+    }
+
+    Ok(Repository::Remote {
+        name,
+        url: first.to_string(),
+        components: rest.iter().map(|c| c.to_string()).collect(),
+        key: key.map(|k| k.to_string()),
+    })
+}
+
+/// Derives the name of a repository from its URL.
+///
+/// Repository files are named after the file, like `docker-ce` for
+/// `https://download.docker.com/linux/fedora/docker-ce.repo`, and other URLs after the domain, like
+/// `docker` for `https://download.docker.com/linux/ubuntu`.
+pub fn derive_name(url: &str) -> Option<String> {
+    let path = url.split_once("://").map_or(url, |(_, p)| p);
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    let host = segments.next()?.split(':').next()?;
+
+    let name = match segments.next_back().and_then(|f| f.rsplit_once('.')) {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => {
+            let labels = host.split('.').collect::<Vec<&str>>();
+            labels[labels.len().saturating_sub(2)]
+        }
+    };
+
+    Some(name.to_string()).filter(|n| is_valid_name(n))
+}
+
+/// Parses the lines that list configured repositories, each with a name optionally followed by a
+/// URL.
+pub fn parse_configured_repositories(lines: &[String]) -> Vec<ConfiguredRepository> {
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            let (name, url) = l.split_once(char::is_whitespace).unwrap_or((l, ""));
+
+            ConfiguredRepository {
+                name: name.to_string(),
+                url: url.trim().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Returns whether text is a URL that repositories and keys can be fetched from.
+fn is_url(text: &str) -> bool {
+    (text.starts_with("https://") || text.starts_with("http://")) && is_valid_word(text)
+}
+
+/// Returns whether a name is fit to name files after.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ['.', '_', '-'].contains(&c))
+}
+
+/// Returns whether a word of a repository is free of whitespace and characters special to shells.
+fn is_valid_word(word: &str) -> bool {
+    !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-:/+~=%@?&,".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_repository_hosted() {
+        assert_eq!(
+            Ok(Repository::Hosted(String::from("ppa:neovim-ppa/stable"))),
+            parse_repository(&["ppa:neovim-ppa/stable"], None, None),
+            "Parses a hosted repository"
+        );
+        assert!(
+            parse_repository(&["neovim"], None, None).is_err(),
+            "Rejects a word that is neither a URL nor a hosted repository"
+        );
+    }
+
+    #[test]
+    fn parse_repository_remote() {
+        assert_eq!(
+            Ok(Repository::Remote {
+                name: String::from("docker"),
+                url: String::from("https://download.docker.com/linux/ubuntu"),
+                components: vec![String::from("jammy"), String::from("stable")],
+                key: Some(String::from("https://download.docker.com/linux/ubuntu/gpg")),
+            }),
+            parse_repository(
+                &[
+                    "https://download.docker.com/linux/ubuntu",
+                    "jammy",
+                    "stable"
+                ],
+                None,
+                Some("https://download.docker.com/linux/ubuntu/gpg")
+            ),
+            "Parses a repository at a URL along with its suite and components"
+        );
+        assert!(
+            parse_repository(&["https://example.com/repo"], Some("../etc"), None).is_err(),
+            "Rejects a name unfit for files"
+        );
+        assert!(
+            parse_repository(&["https://example.com/repo", "$(reboot)"], None, None).is_err(),
+            "Rejects components with characters special to shells"
+        );
+    }
+
+    #[test]
+    fn derive_name_some_values() {
+        assert_eq!(
+            Some(String::from("docker-ce")),
+            derive_name("https://download.docker.com/linux/fedora/docker-ce.repo"),
+            "Names a repository file after the file"
+        );
+        assert_eq!(
+            Some(String::from("docker")),
+            derive_name("https://download.docker.com/linux/ubuntu"),
+            "Names a repository after the domain"
+        );
+    }
+
+    #[test]
+    fn parse_configured_repositories_some_values() {
+        assert_eq!(
+            vec![
+                ConfiguredRepository {
+                    name: String::from("flathub"),
+                    url: String::from("https://dl.flathub.org/repo/"),
+                },
+                ConfiguredRepository {
+                    name: String::from("homebrew/core"),
+                    url: String::new(),
+                },
+            ],
+            parse_configured_repositories(&[
+                String::from("flathub\thttps://dl.flathub.org/repo/"),
+                String::from("homebrew/core"),
+                String::new(),
+            ]),
+            "Parses repositories with and without URLs"
+        );
+    }
+}
@@ -1,49 +1,257 @@
 //! Provides implementation for primary tasks.
 
+use crate::aliases::Aliases;
+use crate::containers::{get_container_distribution, get_container_package_managers, Container};
 use crate::environment::{
-    does_exist, print_list, prompt_for_value_from_list, run_command_and_get_list,
-    run_command_continuous,
+    does_exist, print_list, prompt_for_value_from_list, prompt_for_values_from_list, quote,
+    run_command_and_get_list, run_command_and_get_status, run_command_continuous,
+    run_command_interactive,
 };
-use crate::package_managers::{Installer, PackageManager};
+use crate::journal::{add_entry, format_timestamp, read_entries, Entry, Operation};
+use crate::manifests::{gen_export, ExportFormat, Manifest, ManifestPackage, CASKS_COMMAND};
+use crate::package_managers::{InstallReason, Installer, PackageManager, Update};
+use crate::platforms::{get_native_package_manager, get_operating_platform_name, Distribution};
+use crate::privileges::Privilege;
+use crate::records::{read_records, update_records, Record};
+use crate::repositories::{parse_configured_repositories, ConfiguredRepository, Repository};
+use crate::versions::{parse_version_spec, split_pin};
 use ansi_term::Color;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Items a package manager would remove, along with their sizes in bytes when known.
+type RemovalPreview<'a> = (&'a PackageManager, Vec<(String, Option<u64>)>);
+
+/// Options that alter how tasks are carried out.
+pub struct Options {
+    pub apply_live: bool,
+    pub container: Option<Container>,
+    pub export: bool,
+    pub root: Option<String>,
+    pub privilege: Privilege,
+    pub aliases: Aliases,
+    pub dry_run: bool,
+}
 
 /// Initializes supplementary package managers.
-pub fn init() {
+///
+/// Sets up Flatpak along with the Flathub remote through the native package manager, and an AUR
+/// helper on Arch-based distributions.
+pub fn init(
+    package_managers: &[PackageManager],
+    distribution: &Option<Distribution>,
+    options: &Options,
+) {
+    let distribution = match distribution {
+        Some(d) => d,
+        None => {
+            println!(
+                "{}",
+                Color::Red
+                    .paint("Setting up additional package sources is only supported on Linux!")
+            );
+            return;
+        }
+    };
+
     println!(
-        "Initializing more package sources.. ({}).",
-        Color::Red.paint("Not implemented!")
+        "Initializing more package sources for {}...",
+        Color::Yellow.paint(&distribution.name)
+    );
+
+    let native_package_manager = match get_native_package_manager(package_managers) {
+        Some(p) => p,
+        None => {
+            println!(
+                "{}",
+                Color::Red.paint("Could not find a native package manager for this system!")
+            );
+            return;
+        }
+    };
+
+    // Set up Flatpak along with the Flathub remote
+    if !does_exist("flatpak") {
+        run_setup_step(
+            "flatpak",
+            elevate(
+                native_package_manager,
+                native_package_manager.gen_install_command("flatpak"),
+                options,
+            ),
+            options,
+        );
+    }
+    run_setup_step(
+        "Flathub",
+        options.privilege.gen_command(
+            "flatpak remote-add --if-not-exists flathub https://dl.flathub.org/repo/flathub.flatpakrepo",
+        ),
+        options,
     );
 
-    // TODO: Implement setting up of additional package managers
+    // Set up an AUR helper on Arch-based distributions
+    if distribution.is_like("arch") && !does_exist("paru") {
+        run_setup_step(
+            "paru",
+            Ok(String::from(
+                "git clone https://aur.archlinux.org/paru-bin.git /tmp/paru-bin \
+                 && cd /tmp/paru-bin && makepkg -si --noconfirm; rm -rf /tmp/paru-bin",
+            )),
+            options,
+        );
+    }
+
+    println!("Available package sources:");
+    print_list(&get_available_command_names(package_managers));
+}
+
+/// Runs a step to set up a package source and reports its outcome.
+fn run_setup_step(source: &str, command: Result<String, String>, options: &Options) {
+    println!("Setting up {}...", Color::Yellow.paint(source));
+
+    let command = match command {
+        Ok(c) => c,
+        Err(e) => {
+            println!("{}", Color::Red.paint(e));
+            return;
+        }
+    };
+
+    if options.dry_run {
+        show_command(&command);
+    } else if run_command_continuous(&command).is_err() {
+        println!(
+            "{} {}",
+            Color::Red.paint("There was an error setting up"),
+            Color::Yellow.paint(source)
+        );
+    }
+}
+
+/// Returns the command names of the supplied package managers that exist in the environment.
+fn get_available_command_names(package_managers: &[PackageManager]) -> Vec<String> {
+    package_managers
+        .iter()
+        .filter(|p| does_exist(p.command_name))
+        .map(|p| p.command_name.to_string())
+        .collect::<Vec<String>>()
+}
+
+/// Prints a report of the current system and the package managers relevant to it.
+pub fn info_system(
+    package_managers: &[PackageManager],
+    distribution: &Option<Distribution>,
+    options: &Options,
+) {
+    println!("Platform: {}", get_operating_platform_name());
+
+    if let Some(d) = distribution {
+        println!("Distribution: {} ({})", d.name, d.id);
+        println!(
+            "Version: {}",
+            if d.version_id.is_empty() {
+                "-"
+            } else {
+                &d.version_id
+            }
+        );
+        println!("Immutable: {}", if d.is_immutable { "yes" } else { "no" });
+        println!(
+            "Based on: {}",
+            if d.id_like.is_empty() {
+                String::from("-")
+            } else {
+                d.id_like.join(", ")
+            }
+        );
+    }
+
     println!(
-        "{}",
-        Color::Red.paint("Setting up of additional package managers is not yet implemented!")
+        "Native package manager: {}",
+        get_native_package_manager(package_managers)
+            .map(|p| p.command_name)
+            .unwrap_or("-")
+    );
+    println!(
+        "Available package managers: {}",
+        get_available_command_names(package_managers).join(", ")
+    );
+    println!(
+        "Privilege escalation: {}",
+        options.privilege.get_description()
     );
 }
 
 /// Prints search results for a particular package through the supplied package managers.
-pub fn search(package_managers: &Vec<PackageManager>, package_to_search: &str) {
+///
+/// When a container is supplied, package managers inside it are searched as additional sources.
+pub fn search(
+    package_managers: &[PackageManager],
+    distribution: &Option<Distribution>,
+    package_to_search: &str,
+    options: &Options,
+) {
     println!("Searching {}...", Color::Yellow.paint(package_to_search));
 
+    warn_about_unsupported_root(package_managers, options);
+
     // Print search results from all available package managers
-    print_list(&get_search_results(&package_managers, package_to_search));
+    print_list(&get_search_results(
+        package_managers,
+        package_to_search,
+        None,
+        distribution,
+        options,
+    ));
+
+    // Print search results from package managers inside the container
+    if let Some(container) = &options.container {
+        let container_distribution = get_container_distribution(container);
+
+        print_list(&get_search_results(
+            &get_container_package_managers(&container_distribution),
+            package_to_search,
+            Some(container),
+            &container_distribution,
+            options,
+        ));
+    }
 }
 
 /// Gets search results for a particular package through supplied package managers.
+///
+/// The package is searched by its alias for each package manager on the supplied distribution.
 fn get_search_results(
-    package_managers: &Vec<PackageManager>,
+    package_managers: &[PackageManager],
     package_to_search: &str,
+    container: Option<&Container>,
+    distribution: &Option<Distribution>,
+    options: &Options,
 ) -> Vec<String> {
     // Generate search results across package managers
     let mut list_of_search_results = package_managers
         .iter()
-        .filter(|p| does_exist(p.command_name)) // Filter out package managers that don't exist
-        .map(|p| {
-            get_paired_search_results(
-                // Run the search command and create a decorated list with package managers
-                p.command_name,
-                &run_command_and_get_list(&p.gen_search_command(package_to_search)),
+        .filter(|p| options.root.is_none() || !p.root_key.is_empty()) // Filter out package managers that can't target the root
+        .filter(|p| does_package_manager_exist(p, container)) // Filter out package managers that don't exist
+        .filter_map(|p| {
+            gen_command_for_target(
+                p,
+                p.gen_search_command(options.aliases.get_name(
+                    package_to_search,
+                    distribution,
+                    p.command_name,
+                )),
+                container,
+                false,
+                options,
             )
+            .ok()
+            .map(|c| {
+                // Run the search command and create a decorated list with package managers
+                get_paired_search_results(&get_label(p, container), &run_command_and_get_list(&c))
+            })
         })
         .collect::<Vec<Vec<String>>>();
 
@@ -57,35 +265,342 @@ fn get_search_results(
     all_search_results
 }
 
+/// Returns whether a package manager exists on the host, or inside the supplied container.
+fn does_package_manager_exist(
+    package_manager: &PackageManager,
+    container: Option<&Container>,
+) -> bool {
+    match container {
+        Some(c) => c.does_exist(package_manager.command_name),
+        None => does_exist(package_manager.command_name),
+    }
+}
+
+/// Returns the label of a package manager, qualified with the container it runs inside.
+fn get_label(package_manager: &PackageManager, container: Option<&Container>) -> String {
+    match container {
+        Some(c) => c.get_label(package_manager),
+        None => package_manager.command_name.to_string(),
+    }
+}
+
+/// Adapts a command generated by a package manager to the target system.
+///
+/// Points the command to an alternate root, elevates privileges for it when it needs root, and
+/// wraps it to run inside the supplied container, as requested.
+///
+/// Returns an error when the package manager cannot target the alternate root, or privileges cannot
+/// be elevated.
+fn gen_command_for_target(
+    package_manager: &PackageManager,
+    command: String,
+    container: Option<&Container>,
+    is_elevated: bool,
+    options: &Options,
+) -> Result<String, String> {
+    let command = if is_elevated {
+        elevate(package_manager, command, options)?
+    } else {
+        point_to_root(package_manager, command, options)?
+    };
+
+    Ok(match container {
+        Some(c) => c.gen_command(&command),
+        None => command,
+    })
+}
+
+/// Points a command generated by a package manager to the alternate root, if any.
+fn point_to_root(
+    package_manager: &PackageManager,
+    command: String,
+    options: &Options,
+) -> Result<String, String> {
+    match &options.root {
+        Some(root) => package_manager.gen_rooted_command(&command, root),
+        None => Ok(command),
+    }
+}
+
+/// Warns about available package managers that are left out for not supporting an alternate root.
+fn warn_about_unsupported_root(package_managers: &[PackageManager], options: &Options) {
+    if options.root.is_none() {
+        return;
+    }
+
+    let unsupported_package_managers = package_managers
+        .iter()
+        .filter(|p| p.root_key.is_empty() && does_exist(p.command_name))
+        .map(|p| p.command_name)
+        .collect::<Vec<&str>>();
+
+    if !unsupported_package_managers.is_empty() {
+        println!(
+            "{} {}",
+            Color::Yellow.paint("Skipping package managers that cannot target an alternate root:"),
+            unsupported_package_managers.join(", ")
+        );
+    }
+}
+
 /// Generates pairs of package managers and search results.
-fn get_paired_search_results(package_manager: &str, package_list: &Vec<String>) -> Vec<String> {
+fn get_paired_search_results(package_manager: &str, package_list: &[String]) -> Vec<String> {
     package_list
         .iter()
-        .filter(|p| p.len() > 0) // Filter out the search results with zero packages
+        .filter(|p| !p.is_empty()) // Filter out the search results with zero packages
         .map(|p| format!("{} -> {}", package_manager, p))
         .collect::<Vec<String>>()
 }
 
-/// Attempts installing a particular package using one of the supplied package managers.
-pub fn install(package_managers: &Vec<PackageManager>, package_to_install: &str) {
-    let search_results = get_search_results(package_managers, package_to_install);
+/// Attempts installing packages using the supplied package managers, and prints a summary of the
+/// outcome for each of them.
+///
+/// Every package is searched for across package managers, and a single prompt lets the user choose
+/// among the results of all packages with more than one. Chosen packages are then installed in one
+/// go through each package manager.
+///
+/// Paths to local package files are installed directly through a package manager that supports
+/// their type instead. When a container is supplied, only package managers inside it are used.
+pub fn install(
+    package_managers: &[PackageManager],
+    distribution: &Option<Distribution>,
+    packages_to_install: &[&str],
+    options: &Options,
+) {
+    let container = options.container.as_ref();
+    let container_distribution;
+    let container_package_managers;
+    let (package_managers, distribution) = match container {
+        Some(c) => {
+            container_distribution = get_container_distribution(c);
+            container_package_managers = get_container_package_managers(&container_distribution);
+            (&container_package_managers[..], &container_distribution)
+        }
+        None => (package_managers, distribution),
+    };
 
-    if search_results.len() == 1 {
-        // When there's only a single package
-        install_from_selected_pair(&package_managers, &search_results[0]);
-    } else if search_results.len() == 0 {
-        // When there's no package
-        println!(
-            "{} {}",
-            Color::Red.paint("There were no results found for"),
-            Color::Yellow.paint(package_to_install.to_string())
+    let (files, names): (Vec<&str>, Vec<&str>) = packages_to_install
+        .iter()
+        .partition(|p| is_local_file(package_managers, p));
+
+    let mut outcomes = files
+        .iter()
+        .map(|f| {
+            (
+                f.to_string(),
+                install_local_file(package_managers, f, options),
+            )
+        })
+        .collect::<Vec<(String, Result<String, String>)>>();
+
+    if !names.is_empty() {
+        warn_about_unsupported_root(package_managers, options);
+    }
+
+    // Search for every package, and take the only result where there is just one
+    let mut selected = vec![];
+    let mut ambiguous = vec![];
+    let mut choices = vec![];
+    let mut unpinned = vec![];
+    for name in names {
+        let (name_to_search, mut version) = split_pin(name);
+        if let Some(Err(e)) = version.map(parse_version_spec) {
+            outcomes.push((name.to_string(), Err(e)));
+            continue;
+        }
+
+        let mut search_results = get_search_results(
+            package_managers,
+            name_to_search,
+            container,
+            distribution,
+            options,
         );
-    } else {
-        // Let user choose one of the options
-        let selected_pair = prompt_for_value_from_list(&search_results);
 
-        install_from_selected_pair(&package_managers, &selected_pair);
+        // Names like python@3.11 on Homebrew are packages of their own, rather than versions
+        if version.is_some()
+            && search_results
+                .iter()
+                .any(|r| break_pair_from_search_result(r).1 == name)
+        {
+            search_results.retain(|r| break_pair_from_search_result(r).1 == name);
+            version = None;
+            unpinned.push(name);
+        }
+
+        // Only offer package managers that can install a particular version, when one is asked for
+        let (search_results, unsupported): (Vec<String>, Vec<String>) =
+            search_results.into_iter().partition(|r| {
+                version.is_none()
+                    || package_managers.iter().any(|p| {
+                        !p.install_version_command.is_empty()
+                            && get_label(p, container) == break_pair_from_search_result(r).0
+                    })
+            });
+        if search_results.is_empty() && !unsupported.is_empty() {
+            let mut labels = unsupported
+                .iter()
+                .map(|r| break_pair_from_search_result(r).0)
+                .collect::<Vec<String>>();
+            labels.dedup();
+            outcomes.push((
+                name.to_string(),
+                Err(format!(
+                    "{} cannot install a particular version",
+                    labels.join(", ")
+                )),
+            ));
+            continue;
+        }
+
+        match search_results.len() {
+            0 => outcomes.push((name.to_string(), Err(String::from("no results found")))),
+            1 => selected.push((name.to_string(), search_results[0].to_string())),
+            _ => {
+                ambiguous.push(name);
+                choices.append(&mut get_selection_choices(name, &search_results));
+            }
+        }
+    }
+
+    // Let user choose among the results of every package with more than one
+    if !choices.is_empty() {
+        selected.extend(
+            prompt_for_values_from_list(&choices)
+                .iter()
+                .map(|c| break_selection_choice(c)),
+        );
+    }
+    ambiguous
+        .into_iter()
+        .filter(|n| !selected.iter().any(|(name, _)| name == n))
+        .for_each(|n| outcomes.push((n.to_string(), Err(String::from("nothing chosen")))));
+
+    let selected = selected
+        .into_iter()
+        .map(|(name, pair)| {
+            let (label, identifier) = break_pair_from_search_result(&pair);
+            (label, (name, identifier))
+        })
+        .collect::<Vec<(String, (String, String))>>();
+    let (pinned, selected): (Vec<_>, Vec<_>) = selected.into_iter().partition(|(_, (name, _))| {
+        split_pin(name).1.is_some() && !unpinned.contains(&name.as_str())
+    });
+    for (label, packages) in group_by_backend(pinned) {
+        outcomes.append(&mut install_pinned_packages(
+            package_managers,
+            &label,
+            &packages,
+            options,
+        ));
+    }
+    for (label, packages) in group_by_backend(selected) {
+        outcomes.append(&mut install_selected_packages(
+            package_managers,
+            &label,
+            &packages,
+            options,
+        ));
+    }
+
+    print_summary(&outcomes);
+}
+
+/// Returns whether an argument to install names a local package file rather than a package.
+///
+/// Arguments with the extension of a package file that a package manager can install are taken as
+/// files, and so are existing paths with a `/`, so that a file named like a package in the current
+/// directory is not mistaken for one, nor is a tap like `owner/tap/name` for Homebrew.
+fn is_local_file(package_managers: &[PackageManager], argument: &str) -> bool {
+    package_managers
+        .iter()
+        .any(|p| p.can_install_file(argument))
+        || (argument.contains('/') && Path::new(argument).is_file())
+}
+
+/// Installs a local package file through the first available package manager that supports it.
+///
+/// Returns the outcome to summarize, or an error when the file cannot be installed.
+fn install_local_file(
+    package_managers: &[PackageManager],
+    file_path: &str,
+    options: &Options,
+) -> Result<String, String> {
+    if !Path::new(file_path).is_file() {
+        return Err(String::from("the file does not exist"));
+    }
+
+    let capable_package_managers = package_managers
+        .iter()
+        .filter(|p| p.can_install_file(file_path))
+        .collect::<Vec<&PackageManager>>();
+
+    // Refuse to install through package managers that cannot target an alternate root
+    if options.root.is_some()
+        && !capable_package_managers.is_empty()
+        && capable_package_managers
+            .iter()
+            .all(|p| p.root_key.is_empty())
+    {
+        return Err(String::from(
+            "installing into an alternate root is not supported",
+        ));
     }
+
+    // Use the first of the capable package managers that exists in the environment
+    let package_manager = capable_package_managers
+        .iter()
+        .filter(|p| options.root.is_none() || !p.root_key.is_empty())
+        .find(|p| does_package_manager_exist(p, options.container.as_ref()))
+        .ok_or_else(|| {
+            if capable_package_managers.is_empty() {
+                String::from("no available package manager can install it")
+            } else {
+                format!(
+                    "no available package manager can install it, it requires one of: {}",
+                    capable_package_managers
+                        .iter()
+                        .map(|p| p.command_name)
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                )
+            }
+        })?;
+
+    // Package managers expect an unambiguous path to tell files apart from package names
+    let full_path = match fs::canonicalize(file_path) {
+        Ok(path) => path.to_string_lossy().to_string(),
+        Err(_) => file_path.to_string(),
+    };
+
+    let label = get_label(package_manager, options.container.as_ref());
+    println!(
+        "Installing {} via {}...",
+        Color::Yellow.paint(file_path),
+        label
+    );
+
+    let command = apply_install_options(
+        package_manager,
+        package_manager.gen_local_install_command(&full_path),
+        options,
+    )?;
+
+    // Record the package in the file rather than the file, for it to be looked up and undone later
+    let name = package_manager
+        .get_file_package_name(file_path)
+        .unwrap_or_else(|| file_path.to_string());
+    run_and_record(
+        Operation::Install,
+        package_manager,
+        &[&name],
+        &command,
+        options,
+    )?;
+    export_from_container(&name, options);
+    warn_about_reboot(package_manager, options);
+
+    Ok(format!("installed via {}", label))
 }
 
 /// Generates a pair of package manager and package from a search result.
@@ -95,85 +610,3483 @@ fn break_pair_from_search_result(result_item: &str) -> (String, String) {
     (pair[0].to_string(), pair[1].to_string())
 }
 
-/// Installs a particular package through the appropriate package manager.
-fn install_from_selected_pair(package_managers: &Vec<PackageManager>, result_pair: &str) {
-    let pair = break_pair_from_search_result(&result_pair);
+/// Generates choices to select from among search results for a package, qualified with its name.
+fn get_selection_choices(name: &str, search_results: &[String]) -> Vec<String> {
+    search_results
+        .iter()
+        .map(|r| format!("{}: {}", name, r))
+        .collect()
+}
+
+/// Generates a pair of package name and search result from a selected choice.
+fn break_selection_choice(choice: &str) -> (String, String) {
+    match choice.split_once(": ") {
+        Some((name, result_item)) => (name.to_string(), result_item.to_string()),
+        None => (String::new(), choice.to_string()),
+    }
+}
+
+/// Groups items by the package managers they are paired with, in the order they first appear.
+fn group_by_backend<K: PartialEq, T>(items: Vec<(K, T)>) -> Vec<(K, Vec<T>)> {
+    let mut groups: Vec<(K, Vec<T>)> = vec![];
+
+    for (key, item) in items {
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((key, vec![item])),
+        }
+    }
+
+    groups
+}
+
+/// Installs selected packages in one go through the package manager with the supplied label, and
+/// remembers them by the names they were asked for by.
+///
+/// Returns the outcome to summarize for each package.
+fn install_selected_packages(
+    package_managers: &[PackageManager],
+    label: &str,
+    packages: &[(String, String)],
+    options: &Options,
+) -> Vec<(String, Result<String, String>)> {
+    let identifiers = packages
+        .iter()
+        .map(|(_, i)| i.as_str())
+        .collect::<Vec<&str>>();
 
     println!(
         "Installing {} via {}...",
-        Color::Yellow.paint(&pair.1),
-        pair.0
+        Color::Yellow.paint(identifiers.join(", ")),
+        label
     );
 
-    package_managers
+    let outcome = package_managers
         .iter()
-        .filter(|p| p.command_name == pair.0)
-        .for_each(|p| {
-            let output = run_command_continuous(&p.gen_install_command(&pair.1));
-
-            match output {
-                Ok(_) => println!("{}", Color::Blue.paint("Operation complete!")),
-                Err(_) => println!(
-                    "{} {}",
-                    Color::Red.paint("There was an error installing"),
-                    Color::Yellow.paint(pair.1.to_string())
-                ),
-            }
+        .find(|p| get_label(p, options.container.as_ref()) == label)
+        .ok_or_else(|| format!("{} is not available", label))
+        .and_then(|p| {
+            let command =
+                apply_install_options(p, p.gen_install_packages_command(&identifiers), options)?;
+            run_and_record(Operation::Install, p, &identifiers, &command, options)?;
+            Ok(p)
         });
+
+    if let Ok(p) = outcome {
+        for (name, identifier) in packages {
+            remember_package(p, name, identifier, options);
+            export_from_container(identifier, options);
+        }
+        warn_about_reboot(p, options);
+    }
+
+    packages
+        .iter()
+        .map(|(name, _)| {
+            (
+                name.to_string(),
+                outcome
+                    .as_ref()
+                    .map(|_| format!("installed via {}", label))
+                    .map_err(|e| e.to_string()),
+            )
+        })
+        .collect()
 }
 
-pub fn uninstall(_package_managers: &Vec<PackageManager>, package_to_uninstall: &str) {
-    println!(
-        "Uninstalling {}...",
-        Color::Yellow.paint(package_to_uninstall)
-    );
+/// Installs particular versions of selected packages, requested as `name@version`, in one go
+/// through the package manager with the supplied label, and remembers them by their names.
+///
+/// The requested version is resolved to the latest available one that matches it, so that an exact
+/// version like `13.0.0` installs `13.0.0-2`, and a range to the latest within it. An exact version
+/// is passed on as it is when the available ones cannot be listed. Returns the outcome to
+/// summarize for each package.
+fn install_pinned_packages(
+    package_managers: &[PackageManager],
+    label: &str,
+    packages: &[(String, String)],
+    options: &Options,
+) -> Vec<(String, Result<String, String>)> {
+    let container = options.container.as_ref();
+    let package_manager = match package_managers
+        .iter()
+        .find(|p| get_label(p, container) == label)
+    {
+        Some(p) => p,
+        None => {
+            return packages
+                .iter()
+                .map(|(name, _)| (name.to_string(), Err(format!("{} is not available", label))))
+                .collect()
+        }
+    };
+
+    let mut outcomes = vec![];
+    let mut resolved = vec![];
+    for (name, identifier) in packages {
+        match resolve_pinned_version(package_manager, name, identifier, container) {
+            Ok(version) => resolved.push((name, identifier.as_str(), version)),
+            Err(e) => outcomes.push((name.to_string(), Err(e))),
+        }
+    }
+    if resolved.is_empty() {
+        return outcomes;
+    }
 
-    // TODO: Implement uninstallation of packages
     println!(
-        "{}",
-        Color::Red.paint("Uninstalling packages is not yet implemented!")
+        "Installing {} via {}...",
+        Color::Yellow.paint(
+            resolved
+                .iter()
+                .map(|(_, i, v)| format!("{} {}", i, v))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        label
     );
+
+    let identifiers = resolved.iter().map(|(_, i, _)| *i).collect::<Vec<&str>>();
+    let command = if package_manager.remote_command.is_empty() {
+        Ok(package_manager.gen_install_versions_command(
+            &resolved
+                .iter()
+                .map(|(_, i, v)| (*i, v.as_str()))
+                .collect::<Vec<(&str, &str)>>(),
+        ))
+    } else {
+        // The remote is found for each package, which is then installed one at a time
+        resolved
+            .iter()
+            .map(|(_, i, v)| {
+                resolve_remote(
+                    package_manager,
+                    package_manager.gen_install_version_command(i, v),
+                    i,
+                    container,
+                )
+            })
+            .collect::<Result<Vec<String>, String>>()
+            .map(|c| c.join(" && "))
+    };
+    let outcome = command
+        .and_then(|c| apply_install_options(package_manager, c, options))
+        .and_then(|c| {
+            run_and_record(
+                Operation::Install,
+                package_manager,
+                &identifiers,
+                &c,
+                options,
+            )
+        });
+
+    if outcome.is_ok() {
+        for (name, identifier, _) in &resolved {
+            remember_package(package_manager, split_pin(name).0, identifier, options);
+            export_from_container(identifier, options);
+        }
+        warn_about_reboot(package_manager, options);
+    }
+
+    outcomes.extend(resolved.iter().map(|(name, _, version)| {
+        (
+            name.to_string(),
+            outcome
+                .as_ref()
+                .map(|_| format!("installed {} via {}", version, label))
+                .map_err(|e| e.to_string()),
+        )
+    }));
+    outcomes
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Resolves the version of a package requested as `name@version` to one available through a
+/// package manager.
+///
+/// Versions that cannot be ordered, like channels or commits, are only accepted exactly as asked.
+fn resolve_pinned_version(
+    package_manager: &PackageManager,
+    name: &str,
+    identifier: &str,
+    container: Option<&Container>,
+) -> Result<String, String> {
+    let version = split_pin(name).1.unwrap_or_default();
+    let spec = parse_version_spec(version)?;
+    if !package_manager.does_order_versions {
+        return spec.get_exact().map(|v| v.to_string()).ok_or_else(|| {
+            format!(
+                "{} versions are channels or commits, only an exact one can be asked for",
+                package_manager.command_name
+            )
+        });
+    }
 
-    #[test]
-    fn get_paired_search_results_some_values() {
-        assert_eq!(
-            vec![
-                String::from("pack-man -> One"),
-                String::from("pack-man -> Two"),
-                String::from("pack-man -> Three")
-            ],
-            get_paired_search_results(
-                "pack-man",
-                &vec![
-                    String::from("One"),
-                    String::from("Two"),
-                    String::from("Three")
-                ]
-            ),
-            "Creates a string vector of pairs when supplied with a few values"
-        );
+    let available = get_available_versions(package_manager, identifier, container);
+
+    match spec.resolve(&available) {
+        Some(v) => Ok(v.to_string()),
+        None => match spec.get_exact() {
+            Some(v) if available.is_empty() => Ok(v.to_string()),
+            _ => Err(format!("no available version matches {}", version)),
+        },
     }
+}
 
-    #[test]
-    fn get_paired_search_results_no_value() {
-        assert_eq!(
-            Vec::<String>::new(),
-            get_paired_search_results("pack-man", &Vec::<String>::new()),
-            "Creates a string vector of pairs when supplied with no value"
+/// Gets the versions of a package available through a package manager, on the host or inside the
+/// supplied container.
+fn get_available_versions(
+    package_manager: &PackageManager,
+    package: &str,
+    container: Option<&Container>,
+) -> Vec<String> {
+    if package_manager.versions_command.is_empty() {
+        return vec![];
+    }
+
+    let command = match resolve_remote(
+        package_manager,
+        package_manager.gen_versions_command(package),
+        package,
+        container,
+    ) {
+        Ok(c) => c,
+        Err(_) => return vec![],
+    };
+    run_command_and_get_list(&match container {
+        Some(c) => c.gen_command(&command),
+        None => command,
+    })
+    .into_iter()
+    .filter(|v| !v.is_empty())
+    .collect()
+}
+
+/// Fills in the remote that offers a package into a command that needs one, for package managers
+/// that take packages from named remotes, on the host or inside the supplied container.
+///
+/// Returns an error when no remote offers the package.
+fn resolve_remote(
+    package_manager: &PackageManager,
+    command: String,
+    package: &str,
+    container: Option<&Container>,
+) -> Result<String, String> {
+    if !command.contains("{remote}") {
+        return Ok(command);
+    }
+
+    match find_remote(package_manager, package, container) {
+        Some((_, remote)) => Ok(command.replace("{remote}", &quote(&remote))),
+        None => Err(format!(
+            "no remote of {} offers {}",
+            package_manager.command_name, package
+        )),
+    }
+}
+
+/// Finds the remote that offers a package through a package manager that takes packages from named
+/// remotes, along with the full identifier of the package there, like `org.gnu.emacs` for `emacs`.
+///
+/// A package is looked up by its full identifier first, and then by the last part of it.
+fn find_remote(
+    package_manager: &PackageManager,
+    package: &str,
+    container: Option<&Container>,
+) -> Option<(String, String)> {
+    if package_manager.remote_command.is_empty() {
+        return None;
+    }
+
+    let lookup = package_manager.gen_remote_command(package);
+    let offers = run_command_and_get_list(&match container {
+        Some(c) => c.gen_command(&lookup),
+        None => lookup,
+    })
+    .iter()
+    .filter_map(|l| {
+        let mut words = l.split_whitespace();
+        Some((words.next()?.to_string(), words.next()?.to_string()))
+    })
+    .collect::<Vec<(String, String)>>();
+
+    let suffix = format!(".{}", package.to_lowercase());
+    offers
+        .iter()
+        .find(|(identifier, _)| identifier == package)
+        .or_else(|| {
+            offers
+                .iter()
+                .find(|(identifier, _)| identifier.to_lowercase().ends_with(&suffix))
+        })
+        .map(|(identifier, remotes)| {
+            (
+                identifier.to_string(),
+                remotes.split(',').next().unwrap_or_default().to_string(),
+            )
+        })
+}
+
+/// Formats available versions for a glance, latest first.
+fn format_versions(versions: &[String]) -> String {
+    const COUNT: usize = 5;
+
+    match versions.len() {
+        n if n > COUNT => format!("{} and {} more", versions[..COUNT].join(", "), n - COUNT),
+        _ => versions.join(", "),
+    }
+}
+
+/// Prints a summary of the outcome for each package.
+fn print_summary(outcomes: &[(String, Result<String, String>)]) {
+    println!("Summary:");
+    outcomes.iter().for_each(|(n, outcome)| match outcome {
+        Ok(o) => println!("{}: {}", Color::Yellow.paint(n), Color::Blue.paint(o)),
+        Err(e) => println!("{}: {}", Color::Yellow.paint(n), Color::Red.paint(e)),
+    });
+}
+
+/// Adds the supplied options to a command that installs packages through a package manager.
+///
+/// Returns an error when the command needs root but privileges cannot be elevated.
+fn apply_install_options(
+    package_manager: &PackageManager,
+    command: String,
+    options: &Options,
+) -> Result<String, String> {
+    let command = if options.apply_live && !package_manager.apply_live_key.is_empty() {
+        format!("{} {}", command, package_manager.apply_live_key)
+    } else {
+        command
+    };
+
+    gen_command_for_target(
+        package_manager,
+        command,
+        options.container.as_ref(),
+        true,
+        options,
+    )
+}
+
+/// Elevates privileges for a command generated by a package manager that needs root, after
+/// pointing it to the alternate root, if any.
+///
+/// Commands meant for a container are elevated through `sudo` inside it instead. Returns an error
+/// when the command cannot target the alternate root, or privileges cannot be elevated.
+fn elevate(
+    package_manager: &PackageManager,
+    command: String,
+    options: &Options,
+) -> Result<String, String> {
+    let command = point_to_root(package_manager, command, options)?;
+
+    if !package_manager.does_need_root {
+        Ok(command)
+    } else if options.container.is_some() {
+        Privilege::Tool(String::from("sudo")).gen_command(&command)
+    } else {
+        options.privilege.gen_command(&command)
+    }
+}
+
+/// Exports an installed package from the container to the host, when asked to.
+fn export_from_container(package: &str, options: &Options) {
+    let container = match &options.container {
+        Some(c) if options.export => c,
+        _ => return,
+    };
+
+    match container.gen_export_command(package) {
+        Some(command) => {
+            println!(
+                "Exporting {} from {}...",
+                Color::Yellow.paint(package),
+                container.name
+            );
+
+            if options.dry_run {
+                show_command(&command);
+            } else if run_command_continuous(&command).is_err() {
+                println!(
+                    "{} {}",
+                    Color::Red.paint("There was an error exporting"),
+                    Color::Yellow.paint(package)
+                );
+            }
+        }
+        None => println!(
+            "{}",
+            Color::Yellow.paint("Exporting is only supported from distrobox containers.")
+        ),
+    }
+}
+
+/// Warns when packages installed through a package manager are only available after a reboot.
+fn warn_about_reboot(package_manager: &PackageManager, options: &Options) {
+    if !package_manager.does_need_reboot {
+        return;
+    }
+
+    if options.apply_live && !package_manager.apply_live_key.is_empty() {
+        println!(
+            "{}",
+            Color::Yellow
+                .paint("The changes were applied live, a reboot will make them permanent.")
+        );
+    } else if package_manager.apply_live_key.is_empty() {
+        println!(
+            "{}",
+            Color::Yellow.paint("A reboot is required for the changes to take effect.")
+        );
+    } else {
+        println!(
+            "{}",
+            Color::Yellow.paint(
+                "A reboot is required for the changes to take effect, or use --apply-live next time."
+            )
         );
     }
+}
 
-    #[test]
-    fn break_pair_from_search_result_simple() {
-        assert_eq!(
-            (String::from("pack-man"), String::from("emacs")),
-            break_pair_from_search_result("pack-man -> emacs"),
-            "Breaks a search result into a pair"
+/// Upgrades packages through every available package manager, native ones first, and prints a
+/// summary of the outcome for each of them.
+///
+/// When a package is supplied, only that package is upgraded through the package managers it is
+/// installed through. Packages held on the host are not held in an alternate root.
+pub fn upgrade(package_managers: &[PackageManager], package: Option<&str>, options: &Options) {
+    warn_about_unsupported_root(package_managers, options);
+
+    let mut package_managers = package_managers
+        .iter()
+        .filter(|p| !p.upgrade_key.is_empty()) // Filter out package managers that can't upgrade
+        .filter(|p| options.root.is_none() || !p.root_key.is_empty()) // Filter out package managers that can't target the root
+        .filter(|p| does_exist(p.command_name))
+        .collect::<Vec<&PackageManager>>();
+    package_managers.sort_by_key(|p| p.distributions.is_empty());
+
+    let outcomes = match package {
+        Some(package) => upgrade_package(&package_managers, package, options),
+        None => {
+            if options.root.is_none() {
+                print_held_packages(&get_held_packages(&package_managers));
+            }
+
+            package_managers
+                .iter()
+                .map(|p| {
+                    println!("Upgrading packages via {}...", p.command_name);
+
+                    let outcome = if p.refresh_key.is_empty() {
+                        Ok(())
+                    } else {
+                        run_package_manager_command(p, p.gen_refresh_command(), options)
+                    }
+                    .and_then(|_| {
+                        run_and_record(
+                            Operation::Upgrade,
+                            p,
+                            &[],
+                            &elevate(p, p.gen_upgrade_command(), options)?,
+                            options,
+                        )
+                    });
+
+                    (p.command_name, outcome)
+                })
+                .collect()
+        }
+    };
+
+    if outcomes.is_empty() {
+        return;
+    }
+
+    println!("Summary:");
+    outcomes.iter().for_each(|(p, outcome)| match outcome {
+        Ok(_) => println!("{}: {}", p, Color::Blue.paint("upgraded")),
+        Err(e) => println!("{}: {} ({})", p, Color::Red.paint("failed"), e),
+    });
+}
+
+/// Upgrades a package through the supplied package managers that it is installed through.
+fn upgrade_package(
+    package_managers: &[&PackageManager],
+    package: &str,
+    options: &Options,
+) -> Vec<(&'static str, Result<(), String>)> {
+    let package_managers = package_managers
+        .iter()
+        .filter(|p| !p.upgrade_package_key.is_empty())
+        .copied()
+        .collect::<Vec<&PackageManager>>();
+
+    let installed = get_installed_through(&package_managers, package, options);
+    let held = if options.root.is_none() {
+        get_held_packages(
+            &package_managers
+                .iter()
+                .filter(|p| {
+                    installed
+                        .iter()
+                        .any(|(i, _)| i.command_name == p.command_name)
+                })
+                .copied()
+                .collect::<Vec<&PackageManager>>(),
+        )
+    } else {
+        vec![]
+    };
+    let outcomes = installed
+        .into_iter()
+        .map(|(p, identifier)| {
+            if is_held(&held, p, &identifier) {
+                return (
+                    p.command_name,
+                    Err(String::from("held back, unhold it to upgrade")),
+                );
+            }
+
+            println!(
+                "Upgrading {} via {}...",
+                Color::Yellow.paint(&identifier),
+                p.command_name
+            );
+
+            (
+                p.command_name,
+                elevate(p, p.gen_upgrade_package_command(&identifier), options).and_then(|c| {
+                    run_and_record(Operation::Upgrade, p, &[&identifier], &c, options)
+                }),
+            )
+        })
+        .collect::<Vec<(&str, Result<(), String>)>>();
+
+    if outcomes.is_empty() {
+        println!(
+            "{} {}",
+            Color::Yellow.paint(package),
+            Color::Red.paint("is not installed through a package manager that can upgrade it")
+        );
+    }
+
+    outcomes
+}
+
+/// Holds a package against upgrades through the package manager it is installed through.
+///
+/// Prints the packages held through every available package manager when none is supplied.
+pub fn hold(package_managers: &[PackageManager], package_to_hold: Option<&str>, options: &Options) {
+    if refuse_holds_in_root(options) {
+        return;
+    }
+
+    let package_managers = package_managers
+        .iter()
+        .filter(|p| !p.hold_command.is_empty()) // Filter out package managers that can't hold packages
+        .filter(|p| does_exist(p.command_name))
+        .collect::<Vec<&PackageManager>>();
+
+    let package_to_hold = match package_to_hold {
+        Some(p) => p,
+        None => {
+            let held = get_held_packages(&package_managers);
+            if held.is_empty() {
+                println!("{}", Color::Blue.paint("No packages are held."));
+            }
+            held.iter().for_each(|(p, packages)| {
+                println!("{} ({})", Color::Blue.paint(p.command_name), packages.len());
+                packages
+                    .iter()
+                    .for_each(|package| println!("  {}", package));
+            });
+            return;
+        }
+    };
+
+    let (package_manager, package) =
+        match choose_installed_package(&package_managers, package_to_hold, options) {
+            Some(p) => p,
+            None => return,
+        };
+
+    println!(
+        "Holding {} via {}...",
+        Color::Yellow.paint(&package),
+        package_manager.command_name
+    );
+
+    match run_package_manager_command(
+        package_manager,
+        package_manager.gen_hold_command(&package),
+        options,
+    ) {
+        Ok(_) => println!("{}", Color::Blue.paint("Operation complete!")),
+        Err(_) => println!(
+            "{} {}",
+            Color::Red.paint("There was an error holding"),
+            Color::Yellow.paint(package_to_hold)
+        ),
+    }
+}
+
+/// Releases a held package through the package manager it is held through, for upgrades to
+/// include it again.
+pub fn unhold(package_managers: &[PackageManager], package_to_unhold: &str, options: &Options) {
+    if refuse_holds_in_root(options) {
+        return;
+    }
+
+    let package_managers = package_managers
+        .iter()
+        .filter(|p| !p.unhold_command.is_empty()) // Filter out package managers that can't hold packages
+        .filter(|p| does_exist(p.command_name))
+        .collect::<Vec<&PackageManager>>();
+
+    // Look for the package by its name, as well as by the identifiers it was installed with
+    let remembered = get_remembered_packages(&package_managers, package_to_unhold, options);
+    let held_packages = get_held_packages(&package_managers);
+    let held = package_managers
+        .iter()
+        .filter_map(|p| {
+            remembered
+                .iter()
+                .filter(|(r, _)| r.command_name == p.command_name)
+                .map(|(_, i)| i.as_str())
+                .chain(std::iter::once(package_to_unhold))
+                .find(|i| is_held(&held_packages, p, i))
+                .map(|i| (*p, i.to_string()))
+        })
+        .collect::<Vec<(&PackageManager, String)>>();
+
+    if held.is_empty() {
+        println!(
+            "{} {}",
+            Color::Yellow.paint(package_to_unhold),
+            Color::Red.paint("is not held through an available package manager")
+        );
+        return;
+    }
+
+    let (package_manager, package) = match choose_package_manager(held) {
+        Some(p) => p,
+        None => return,
+    };
+
+    println!(
+        "Releasing {} via {}...",
+        Color::Yellow.paint(&package),
+        package_manager.command_name
+    );
+
+    match run_package_manager_command(
+        package_manager,
+        package_manager.gen_unhold_command(&package),
+        options,
+    ) {
+        Ok(_) => println!("{}", Color::Blue.paint("Operation complete!")),
+        Err(_) => println!(
+            "{} {}",
+            Color::Red.paint("There was an error releasing"),
+            Color::Yellow.paint(package_to_unhold)
+        ),
+    }
+}
+
+/// Refuses to hold or release packages in an alternate root, as holds are kept in the
+/// configuration of the host, like `/etc/pacman.conf`. Returns whether it refused.
+fn refuse_holds_in_root(options: &Options) -> bool {
+    if options.root.is_some() {
+        println!(
+            "{}",
+            Color::Red.paint("Holding packages in an alternate root is not supported")
+        );
+    }
+
+    options.root.is_some()
+}
+
+/// Gets packages held through the supplied package managers, paired with the package managers.
+///
+/// Package managers without held packages are left out.
+fn get_held_packages<'a>(
+    package_managers: &[&'a PackageManager],
+) -> Vec<(&'a PackageManager, Vec<String>)> {
+    package_managers
+        .iter()
+        .filter(|p| !p.list_held_command.is_empty()) // Filter out package managers that can't hold packages
+        .map(|p| {
+            (
+                *p,
+                run_command_and_get_list(p.list_held_command)
+                    .into_iter()
+                    .filter(|l| !l.is_empty())
+                    .collect::<Vec<String>>(),
+            )
+        })
+        .filter(|(_, packages)| !packages.is_empty())
+        .collect()
+}
+
+/// Returns whether a package is held through a package manager, among the supplied held packages.
+fn is_held(
+    held: &[(&PackageManager, Vec<String>)],
+    package_manager: &PackageManager,
+    package: &str,
+) -> bool {
+    held.iter()
+        .filter(|(p, _)| p.command_name == package_manager.command_name)
+        .any(|(_, packages)| packages.iter().any(|p| p == package))
+}
+
+/// Prints packages held back from upgrades, when there are any.
+fn print_held_packages(held: &[(&PackageManager, Vec<String>)]) {
+    if held.is_empty() {
+        return;
+    }
+
+    println!(
+        "{}",
+        Color::Yellow.paint("Packages held back from upgrades:")
+    );
+    held.iter()
+        .for_each(|(p, packages)| println!("  {}: {}", p.command_name, packages.join(", ")));
+}
+
+/// Runs a command generated by a package manager, elevating privileges when it needs root.
+fn run_package_manager_command(
+    package_manager: &PackageManager,
+    command: String,
+    options: &Options,
+) -> Result<(), String> {
+    let command = elevate(package_manager, command, options)?;
+
+    if options.dry_run {
+        show_command(&command);
+        Ok(())
+    } else {
+        run_command_continuous(&command).map_err(|e| e.to_string())
+    }
+}
+
+/// Shows a command instead of running it, for a dry run.
+fn show_command(command: &str) {
+    println!("{} {}", Color::Yellow.paint("Would run:"), command);
+}
+
+/// Shows a command that changes packages through a package manager instead of running it, and
+/// runs the simulation of the package manager in its place where there is one.
+///
+/// Commands meant for a container are not simulated.
+fn simulate_command(package_manager: &PackageManager, command: &str, options: &Options) {
+    show_command(command);
+
+    let simulated_command = match package_manager.gen_simulated_command(command) {
+        Some(c) if options.container.is_none() => c,
+        _ => return,
+    };
+
+    println!("Simulating through {}...", package_manager.command_name);
+    if run_command_continuous(&simulated_command).is_err() {
+        println!(
+            "{}",
+            Color::Red.paint("There was an error running the simulation")
+        );
+    }
+}
+
+/// Runs a command that changes packages through a package manager, and records the operation in
+/// the journal for each of the packages, along with their versions before and after it.
+///
+/// Operations on every package are recorded once, without a package. Versions are only looked up
+/// for packages on the host system. Nothing is recorded for a dry run, which simulates the command
+/// instead.
+fn run_and_record(
+    operation: Operation,
+    package_manager: &PackageManager,
+    packages: &[&str],
+    command: &str,
+    options: &Options,
+) -> Result<(), String> {
+    run_and_record_with(operation, package_manager, packages, command, None, options)
+}
+
+/// Runs a command that changes packages through a package manager like [`run_and_record`], also
+/// recording the ID of the operation it reverses, if any.
+fn run_and_record_with(
+    operation: Operation,
+    package_manager: &PackageManager,
+    packages: &[&str],
+    command: &str,
+    reverses: Option<u64>,
+    options: &Options,
+) -> Result<(), String> {
+    if options.dry_run {
+        simulate_command(package_manager, command, options);
+        return Ok(());
+    }
+
+    let packages = if packages.is_empty() {
+        &[""][..]
+    } else {
+        packages
+    };
+    let get_versions = || {
+        packages
+            .iter()
+            .map(|p| {
+                if p.is_empty() || options.container.is_some() || options.root.is_some() {
+                    String::new()
+                } else {
+                    get_installed_version(package_manager, p)
+                }
+            })
+            .collect::<Vec<String>>()
+    };
+
+    let versions_before = get_versions();
+    let status = run_command_and_get_status(command).map_err(|e| e.to_string())?;
+    let versions_after = get_versions();
+    let transaction = if package_manager.last_transaction_command.is_empty()
+        || options.container.is_some()
+        || options.root.is_some()
+    {
+        String::new()
+    } else {
+        run_command_and_get_list(package_manager.last_transaction_command)
+            .first()
+            .map(|t| t.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    for (i, package) in packages.iter().enumerate() {
+        let mut entry = Entry::new(
+            operation,
+            &get_label(package_manager, options.container.as_ref()),
+            package,
+            command,
+        );
+        entry.version_before = versions_before[i].to_string();
+        entry.version_after = versions_after[i].to_string();
+        entry.status = status;
+        entry.transaction = transaction.to_string();
+        entry.reverses = reverses;
+
+        if let Err(e) = add_entry(entry) {
+            println!(
+                "{} {}",
+                Color::Yellow.paint("The operation could not be recorded in the journal:"),
+                e
+            );
+        }
+    }
+
+    match status {
+        0 => Ok(()),
+        code => Err(format!("The command exited with {}.", code)),
+    }
+}
+
+/// Prints information about a package from every package manager that offers it.
+///
+/// Information from multiple package managers is shown side by side for comparison.
+pub fn info(package_managers: &[PackageManager], package: &str) {
+    println!(
+        "Getting information about {}...",
+        Color::Yellow.paint(package)
+    );
+
+    let details = package_managers
+        .iter()
+        .filter(|p| !p.info_key.is_empty()) // Filter out package managers that can't show information
+        .filter(|p| does_exist(p.command_name))
+        .filter_map(|p| {
+            // Package managers that take packages from remotes know them by their full identifiers
+            let (identifier, command) = match find_remote(p, package, None) {
+                Some((identifier, remote)) => (
+                    identifier.to_string(),
+                    p.gen_info_command(&identifier)
+                        .replace("{remote}", &quote(&remote)),
+                ),
+                None if p.info_key.contains("{remote}") => return None,
+                None => (package.to_string(), p.gen_info_command(package)),
+            };
+
+            Some((
+                p,
+                identifier,
+                p.parse_info(&run_command_and_get_list(&command)),
+            ))
+        })
+        .filter(|(_, _, fields)| !fields.is_empty()) // Filter out package managers that don't offer the package
+        .map(|(p, identifier, mut fields)| {
+            fields.push(("Installed", get_install_state(p, &identifier)));
+            fields.push((
+                "Available",
+                format_versions(&get_available_versions(p, &identifier, None)),
+            ));
+            (p.command_name, fields)
+        })
+        .collect::<Vec<(&str, Vec<(&str, String)>)>>();
+
+    if details.is_empty() {
+        println!(
+            "{} {}",
+            Color::Red.paint("There were no results found for"),
+            Color::Yellow.paint(package)
+        );
+        return;
+    }
+
+    print_list(&get_info_table(&details));
+}
+
+/// Returns whether a package is installed through a package manager.
+fn is_installed(package_manager: &PackageManager, package: &str) -> bool {
+    !get_installed_version(package_manager, package).is_empty()
+}
+
+/// Gets the installed version of a package through a package manager.
+///
+/// Returns an empty string when the package is not installed, or its version cannot be found.
+fn get_installed_version(package_manager: &PackageManager, package: &str) -> String {
+    if package_manager.installed_version_command.is_empty() {
+        return String::new();
+    }
+
+    run_command_and_get_list(&package_manager.gen_installed_version_command(package))[0].to_string()
+}
+
+/// Gets a readable install state of a package through a package manager.
+fn get_install_state(package_manager: &PackageManager, package: &str) -> String {
+    if package_manager.installed_version_command.is_empty() {
+        return String::from("unknown");
+    }
+
+    match get_installed_version(package_manager, package).as_str() {
+        "" => String::from("no"),
+        version => format!("yes ({})", version),
+    }
+}
+
+/// Generates rows of a table with package information from package managers side by side.
+///
+/// Values are shortened when there is more than one package manager to compare.
+fn get_info_table(details: &[(&str, Vec<(&str, String)>)]) -> Vec<String> {
+    let max_width = if details.len() > 1 { 40 } else { usize::MAX };
+    let shorten = |value: &str| {
+        if value.chars().count() > max_width {
+            format!(
+                "{}...",
+                value.chars().take(max_width - 3).collect::<String>()
+            )
+        } else if value.is_empty() {
+            String::from("-")
+        } else {
+            value.to_string()
+        }
+    };
+
+    // Generate rows of cells, starting with the package managers
+    let mut rows = vec![std::iter::once(String::from("Source"))
+        .chain(details.iter().map(|(p, _)| p.to_string()))
+        .collect::<Vec<String>>()];
+    for (i, (field, _)) in details[0].1.iter().enumerate() {
+        rows.push(
+            std::iter::once(field.to_string())
+                .chain(details.iter().map(|(_, fields)| shorten(&fields[i].1)))
+                .collect(),
+        );
+    }
+
+    get_aligned_rows(&rows)
+}
+
+/// Pads cells in rows to align them in columns.
+fn get_aligned_rows(rows: &[Vec<String>]) -> Vec<String> {
+    let widths = (0..rows[0].len())
+        .map(|c| rows.iter().map(|r| r[c].chars().count()).max().unwrap_or(0))
+        .collect::<Vec<usize>>();
+
+    rows.iter()
+        .map(|r| {
+            r.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Removes orphaned dependencies through every available package manager, after a preview of
+/// them along with the space they take.
+///
+/// Prompts to proceed after the preview, unless asked not to.
+pub fn autoremove(package_managers: &[PackageManager], assume_yes: bool, options: &Options) {
+    let previews = get_removal_previews(package_managers, |p| {
+        (p.list_orphans_command, p.autoremove_command)
+    });
+
+    if previews.is_empty() {
+        println!(
+            "{}",
+            Color::Blue.paint("There are no orphaned packages to remove!")
+        );
+        return;
+    }
+
+    print_removal_previews("Orphaned packages", &previews);
+    if !assume_yes && !options.dry_run && !confirm_removal() {
+        return;
+    }
+
+    let outcomes = previews
+        .iter()
+        .map(|(p, items)| {
+            println!("Removing orphaned packages via {}...", p.command_name);
+
+            let packages = items.iter().map(|(n, _)| n.as_str()).collect::<Vec<&str>>();
+            let outcome = elevate(p, p.autoremove_command.to_string(), options)
+                .and_then(|c| run_and_record(Operation::Uninstall, p, &packages, &c, options))
+                .map(|_| {
+                    format!(
+                        "removed {} packages, reclaiming {}",
+                        items.len(),
+                        format_total_size(items)
+                    )
+                });
+
+            (p.command_name, outcome)
+        })
+        .collect::<Vec<(&str, Result<String, String>)>>();
+
+    print_removal_summary(&outcomes);
+}
+
+/// Cleans package caches through every available package manager, after a preview of what will be
+/// removed along with the space it takes.
+///
+/// Prompts to proceed after the preview, unless asked not to.
+pub fn clean(package_managers: &[PackageManager], assume_yes: bool, options: &Options) {
+    let previews = get_removal_previews(package_managers, |p| {
+        (p.list_cache_command, p.clean_command)
+    });
+
+    if previews.is_empty() {
+        println!(
+            "{}",
+            Color::Blue.paint("There are no package caches to clean!")
+        );
+        return;
+    }
+
+    print_removal_previews("Package caches", &previews);
+    if !assume_yes && !options.dry_run && !confirm_removal() {
+        return;
+    }
+
+    let outcomes = previews
+        .iter()
+        .map(|(p, items)| {
+            println!("Cleaning package caches via {}...", p.command_name);
+
+            let outcome = run_package_manager_command(p, p.clean_command.to_string(), options)
+                .map(|_| format!("cleaned, reclaiming {}", format_total_size(items)));
+
+            (p.command_name, outcome)
+        })
+        .collect::<Vec<(&str, Result<String, String>)>>();
+
+    print_removal_summary(&outcomes);
+}
+
+/// Gets items that every available package manager would remove, along with their sizes.
+///
+/// The supplied function picks the command that lists the items, and the command that removes
+/// them, of a package manager. Package managers with nothing to remove are left out, and so are
+/// those that remove through a tool that is not available, like `paccache` for pacman.
+fn get_removal_previews(
+    package_managers: &[PackageManager],
+    get_commands: fn(&PackageManager) -> (&'static str, &'static str),
+) -> Vec<RemovalPreview<'_>> {
+    package_managers
+        .iter()
+        .filter(|p| {
+            let (list_command, remove_command) = get_commands(p);
+            !list_command.is_empty() && !remove_command.is_empty()
+        }) // Filter out package managers that can't preview or remove
+        .filter(|p| does_exist(p.command_name))
+        .filter(|p| match get_missing_tool(get_commands(p).1) {
+            Some(tool) => {
+                println!(
+                    "{} {} {}",
+                    Color::Yellow.paint(p.command_name),
+                    Color::Yellow.paint("was skipped, as it needs"),
+                    Color::Yellow.paint(tool)
+                );
+                false
+            }
+            None => true,
+        }) // Filter out package managers that remove through a tool that is not available
+        .map(|p| {
+            (
+                p,
+                parse_sized_items(&run_command_and_get_list(get_commands(p).0)),
+            )
+        })
+        .filter(|(_, items)| !items.is_empty())
+        .collect()
+}
+
+/// Parses lines of items optionally followed by their sizes in bytes.
+fn parse_sized_items(lines: &[String]) -> Vec<(String, Option<u64>)> {
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| match l.rsplit_once(' ') {
+            Some((item, size)) if size.parse::<u64>().is_ok() => {
+                (item.trim().to_string(), size.parse().ok())
+            }
+            _ => (l.to_string(), None),
+        })
+        .collect()
+}
+
+/// Formats a size in bytes in the largest fitting binary unit.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Formats the total size of items, noting when the sizes of some of them are unknown.
+fn format_total_size(items: &[(String, Option<u64>)]) -> String {
+    let total = items.iter().filter_map(|(_, s)| *s).sum::<u64>();
+
+    if items.iter().all(|(_, s)| s.is_none()) {
+        String::from("an unknown amount of space")
+    } else if items.iter().any(|(_, s)| s.is_none()) {
+        format!("at least {}", format_size(total))
+    } else {
+        format_size(total)
+    }
+}
+
+/// Prints items that package managers would remove, along with their sizes and the total.
+fn print_removal_previews(title: &str, previews: &[RemovalPreview]) {
+    previews.iter().for_each(|(p, items)| {
+        println!("{} via {}:", title, Color::Blue.paint(p.command_name));
+        print_list(
+            &get_aligned_rows(
+                &items
+                    .iter()
+                    .map(|(item, size)| {
+                        vec![
+                            item.to_string(),
+                            size.map_or(String::from("-"), format_size),
+                        ]
+                    })
+                    .collect::<Vec<Vec<String>>>(),
+            )
+            .iter()
+            .map(|r| format!("  {}", r))
+            .collect::<Vec<String>>(),
+        );
+    });
+
+    println!(
+        "{} {}",
+        Color::Yellow.paint("Space to reclaim:"),
+        format_total_size(
+            &previews
+                .iter()
+                .flat_map(|(_, items)| items.iter().cloned())
+                .collect::<Vec<(String, Option<u64>)>>()
+        )
+    );
+}
+
+/// Prompts to proceed with a removal, and returns whether to.
+fn confirm_removal() -> bool {
+    println!("Proceed with the removal?");
+
+    prompt_for_value_from_list(&vec![String::from("Proceed"), String::from("Cancel")]) == "Proceed"
+}
+
+/// Prints a summary of the outcome of a removal for each package manager.
+fn print_removal_summary(outcomes: &[(&str, Result<String, String>)]) {
+    println!("Summary:");
+    outcomes.iter().for_each(|(p, outcome)| match outcome {
+        Ok(o) => println!("{}: {}", p, Color::Blue.paint(o)),
+        Err(e) => println!("{}: {} ({})", p, Color::Red.paint("failed"), e),
+    });
+}
+
+/// Lists repositories configured for every available package manager, or only the named one.
+pub fn repo_list(package_managers: &[PackageManager], backend: Option<&str>) {
+    let listed = get_repo_package_managers(package_managers, backend)
+        .into_iter()
+        .filter(|p| !p.list_repos_command.is_empty()) // Filter out package managers that can't list repositories
+        .map(|p| {
+            (
+                p,
+                parse_configured_repositories(&run_command_and_get_list(p.list_repos_command)),
+            )
+        })
+        .filter(|(_, repositories)| !repositories.is_empty())
+        .collect::<Vec<(&PackageManager, Vec<ConfiguredRepository>)>>();
+
+    if listed.is_empty() {
+        println!("{}", Color::Blue.paint("No repositories were found."));
+    }
+    listed.iter().for_each(|(p, repositories)| {
+        println!(
+            "{} ({})",
+            Color::Blue.paint(p.command_name),
+            repositories.len()
+        );
+        print_list(
+            &get_aligned_rows(
+                &repositories
+                    .iter()
+                    .map(|r| vec![r.name.to_string(), r.url.to_string()])
+                    .collect::<Vec<Vec<String>>>(),
+            )
+            .iter()
+            .map(|r| format!("  {}", r.trim_end()))
+            .collect::<Vec<String>>(),
+        );
+    });
+}
+
+/// Adds a repository through the package manager that takes it.
+///
+/// Hosted repositories go to the package manager with their prefix, like `ppa:` for APT, and
+/// repositories at URLs to the one for the kind of file they point to, or to APT otherwise.
+/// Prompts to choose a package manager when more than one takes the repository. Shows the files
+/// and commands that would change the system instead, for a dry run.
+pub fn repo_add(
+    package_managers: &[PackageManager],
+    repository: &Repository,
+    backend: Option<&str>,
+    options: &Options,
+) {
+    let package_managers = get_repo_package_managers(package_managers, backend);
+
+    let candidates = match repository {
+        Repository::Hosted(r) => package_managers
+            .iter()
+            .filter_map(|p| p.get_hosted_repo(r).map(|h| (*p, h.to_string())))
+            .collect::<Vec<(&PackageManager, String)>>(),
+        Repository::Remote { name, url, .. } => {
+            let takers = package_managers
+                .iter()
+                .filter(|p| !p.add_repo_command.is_empty())
+                .collect::<Vec<&&PackageManager>>();
+            let file_takers = takers
+                .iter()
+                .filter(|p| p.can_add_repo_file(url))
+                .collect::<Vec<&&&PackageManager>>();
+
+            if file_takers.is_empty() {
+                takers
+                    .iter()
+                    .filter(|p| p.repo_extension.is_empty())
+                    .map(|p| (**p, name.to_string()))
+                    .collect()
+            } else {
+                file_takers
+                    .iter()
+                    .map(|p| (***p, name.to_string()))
+                    .collect()
+            }
+        }
+    };
+
+    let (package_manager, hosted_or_name) = match choose_package_manager(candidates) {
+        Some(c) => c,
+        None => {
+            println!(
+                "{}",
+                Color::Red.paint("None of the available package managers can add the repository!")
+            );
+            return;
+        }
+    };
+
+    let (command, files) = match repository {
+        Repository::Hosted(_) => (
+            package_manager.gen_add_hosted_repo_command(&hosted_or_name),
+            vec![],
+        ),
+        Repository::Remote {
+            name,
+            url,
+            components,
+            key,
+        } => {
+            if let Err(e) = validate_remote_repo(package_manager, components, key.as_deref()) {
+                println!("{}", Color::Red.paint(e));
+                return;
+            }
+
+            (
+                package_manager.gen_add_repo_command(
+                    name,
+                    url,
+                    &components.iter().map(|c| c.as_str()).collect::<Vec<&str>>(),
+                    key.as_deref(),
+                ),
+                package_manager.get_repo_files(package_manager.add_repo_command, name),
+            )
+        }
+    };
+
+    println!(
+        "Adding repository {} via {}...",
+        Color::Yellow.paint(&hosted_or_name),
+        package_manager.command_name
+    );
+
+    match change_repos(package_manager, command, &files, options) {
+        Ok(_) => println!("{}", Color::Blue.paint("Operation complete!")),
+        Err(e) => println!(
+            "{} {} ({})",
+            Color::Red.paint("There was an error adding"),
+            Color::Yellow.paint(&hosted_or_name),
+            e
+        ),
+    }
+}
+
+/// Removes a repository, either hosted like `ppa:owner/name` or configured by its listed name.
+///
+/// Prompts to choose a package manager when more than one has a repository with the name. Shows
+/// the files and commands that would change the system instead, for a dry run.
+pub fn repo_remove(
+    package_managers: &[PackageManager],
+    repository: &str,
+    backend: Option<&str>,
+    options: &Options,
+) {
+    let package_managers = get_repo_package_managers(package_managers, backend);
+
+    // Look for a package manager with the prefix of the repository, or with it configured
+    let hosted = package_managers
+        .iter()
+        .filter(|p| !p.hosted_repo_prefix.is_empty())
+        .filter_map(|p| p.get_hosted_repo(repository).map(|h| (*p, h.to_string())))
+        .collect::<Vec<(&PackageManager, String)>>();
+    let is_hosted = !hosted.is_empty();
+    let candidates = if !is_hosted {
+        package_managers
+            .iter()
+            .filter(|p| {
+                !p.remove_repo_command.is_empty() || p.get_hosted_repo(repository).is_some()
+            })
+            .filter(|p| {
+                parse_configured_repositories(&run_command_and_get_list(p.list_repos_command))
+                    .iter()
+                    .any(|r| r.name == repository)
+            })
+            .map(|p| (*p, repository.to_string()))
+            .collect()
+    } else {
+        hosted
+    };
+
+    let (package_manager, name) = match choose_package_manager(candidates) {
+        Some(c) => c,
+        None => {
+            println!(
+                "{} {}",
+                Color::Red.paint("No repository could be found with the name"),
+                Color::Yellow.paint(repository)
+            );
+            return;
+        }
+    };
+
+    let (command, files) = if is_hosted || package_manager.remove_repo_command.is_empty() {
+        (
+            package_manager.gen_remove_hosted_repo_command(&name),
+            vec![],
+        )
+    } else {
+        (
+            package_manager.gen_remove_repo_command(&name),
+            package_manager.get_repo_files(package_manager.remove_repo_command, &name),
+        )
+    };
+
+    println!(
+        "Removing repository {} via {}...",
+        Color::Yellow.paint(repository),
+        package_manager.command_name
+    );
+
+    match change_repos(package_manager, command, &files, options) {
+        Ok(_) => println!("{}", Color::Blue.paint("Operation complete!")),
+        Err(e) => println!(
+            "{} {} ({})",
+            Color::Red.paint("There was an error removing"),
+            Color::Yellow.paint(repository),
+            e
+        ),
+    }
+}
+
+/// Gets the available package managers for managing repositories, or only the named one.
+///
+/// Reports the available package managers that have no repositories to manage, like snap, as not
+/// applicable and leaves them out.
+fn get_repo_package_managers<'a>(
+    package_managers: &'a [PackageManager],
+    backend: Option<&str>,
+) -> Vec<&'a PackageManager> {
+    let (package_managers, inapplicable): (Vec<&PackageManager>, Vec<&PackageManager>) =
+        package_managers
+            .iter()
+            .filter(|p| backend.map_or(true, |b| b == p.command_name))
+            .filter(|p| does_exist(p.command_name))
+            .partition(|p| {
+                !p.list_repos_command.is_empty()
+                    || !p.add_repo_command.is_empty()
+                    || !p.add_hosted_repo_command.is_empty()
+            });
+
+    if !inapplicable.is_empty() {
+        println!(
+            "{} {}",
+            Color::Yellow.paint("Repositories are not applicable to:"),
+            inapplicable
+                .iter()
+                .map(|p| p.command_name)
+                .collect::<Vec<&str>>()
+                .join(", ")
+        );
+    }
+
+    package_managers
+}
+
+/// Checks that a repository at a URL comes with what the package manager needs to add it.
+///
+/// APT needs the suite of the repository along with the key it is signed with, for a `signed-by`
+/// keyring, while others take neither.
+fn validate_remote_repo(
+    package_manager: &PackageManager,
+    components: &[String],
+    key: Option<&str>,
+) -> Result<(), String> {
+    let takes_components = package_manager.add_repo_command.contains("{components}");
+    let takes_key = package_manager.add_repo_command.contains("{key}");
+
+    if takes_components && components.is_empty() {
+        Err(format!(
+            "{} needs the suite and components after the URL, like 'jammy main'.",
+            package_manager.command_name
+        ))
+    } else if !takes_components && !components.is_empty() {
+        Err(format!(
+            "{} takes only the URL of the repository.",
+            package_manager.command_name
+        ))
+    } else if takes_key && key.is_none() {
+        Err(format!(
+            "{} needs the URL of the key the repository is signed with, through --key.",
+            package_manager.command_name
+        ))
+    } else if !takes_key && key.is_some() {
+        Err(format!(
+            "{} takes the key from the repository itself.",
+            package_manager.command_name
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Runs a command that changes the repositories of a package manager, showing the files it would
+/// change first for a dry run.
+fn change_repos(
+    package_manager: &PackageManager,
+    command: String,
+    files: &[String],
+    options: &Options,
+) -> Result<(), String> {
+    if options.dry_run && !files.is_empty() {
+        println!("{}", Color::Yellow.paint("Files that would change:"));
+        files.iter().for_each(|f| println!("  {}", f));
+    }
+
+    run_package_manager_command(package_manager, command, options)
+}
+
+/// Shows the tree of packages that a package depends on, through the package manager it is
+/// installed through, down to the supplied depth.
+pub fn deps(package_managers: &[PackageManager], package: &str, depth: usize, options: &Options) {
+    show_dependency_tree(package_managers, package, depth, false, options);
+}
+
+/// Shows the tree of installed packages that depend on a package, through the package manager it
+/// is installed through, down to the supplied depth.
+pub fn rdeps(package_managers: &[PackageManager], package: &str, depth: usize, options: &Options) {
+    show_dependency_tree(package_managers, package, depth, true, options);
+}
+
+/// Shows the tree of dependencies of a package, or of packages that depend on it when reversed.
+///
+/// Prompts to choose a package manager when the package is installed through more than one.
+fn show_dependency_tree(
+    package_managers: &[PackageManager],
+    package: &str,
+    depth: usize,
+    reverse: bool,
+    options: &Options,
+) {
+    let package_managers = package_managers
+        .iter()
+        .filter(|p| {
+            let command = if reverse {
+                p.rdeps_command
+            } else {
+                p.deps_command
+            };
+            !command.is_empty()
+        }) // Filter out package managers that can't query dependencies
+        .filter(|p| does_exist(p.command_name))
+        .collect::<Vec<&PackageManager>>();
+
+    let (package_manager, identifier) =
+        match choose_installed_package(&package_managers, package, options) {
+            Some(p) => p,
+            None => return,
+        };
+
+    let tree = get_dependency_tree(&identifier, depth, &mut vec![], &|p| {
+        get_related_packages(package_manager, p, reverse)
+    });
+
+    println!(
+        "{} {} via {}:",
+        if reverse {
+            "Packages that depend on"
+        } else {
+            "Dependencies of"
+        },
+        Color::Yellow.paint(package),
+        package_manager.command_name
+    );
+    if tree.children.is_empty() {
+        println!("{}", Color::Blue.paint("None found."));
+    } else {
+        print_list(&get_tree_lines(&tree));
+    }
+}
+
+/// A package along with the packages it depends on, or that depend on it.
+///
+/// Packages already in the tree are marked as repeated instead of being expanded again.
+#[derive(Debug, PartialEq)]
+struct DependencyNode {
+    name: String,
+    children: Vec<DependencyNode>,
+    is_repeated: bool,
+}
+
+/// Gets the packages a package directly depends on through a package manager, or the installed
+/// ones that directly depend on it when reversed.
+fn get_related_packages(
+    package_manager: &PackageManager,
+    package: &str,
+    reverse: bool,
+) -> Vec<String> {
+    let command = if reverse {
+        package_manager.gen_rdeps_command(package)
+    } else {
+        package_manager.gen_deps_command(package)
+    };
+
+    run_command_and_get_list(&command)
+        .iter()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty() && l != package)
+        .collect()
+}
+
+/// Builds the tree of packages related to a package down to the supplied depth, through the
+/// supplied function that gets the packages directly related to one.
+fn get_dependency_tree(
+    package: &str,
+    depth: usize,
+    seen: &mut Vec<String>,
+    get_related: &dyn Fn(&str) -> Vec<String>,
+) -> DependencyNode {
+    seen.push(package.to_string());
+
+    let children = if depth == 0 {
+        vec![]
+    } else {
+        get_related(package)
+            .into_iter()
+            .map(|p| {
+                if seen.contains(&p) {
+                    DependencyNode {
+                        name: p,
+                        children: vec![],
+                        is_repeated: true,
+                    }
+                } else {
+                    get_dependency_tree(&p, depth - 1, seen, get_related)
+                }
+            })
+            .collect()
+    };
+
+    DependencyNode {
+        name: package.to_string(),
+        children,
+        is_repeated: false,
+    }
+}
+
+/// Gets the lines of a tree of packages, drawn with branches.
+fn get_tree_lines(tree: &DependencyNode) -> Vec<String> {
+    let mut lines = vec![tree.name.to_string()];
+    add_tree_lines(&tree.children, "", &mut lines);
+
+    lines
+}
+
+/// Adds the lines of branches of a tree of packages, below the supplied prefix.
+fn add_tree_lines(nodes: &[DependencyNode], prefix: &str, lines: &mut Vec<String>) {
+    for (i, node) in nodes.iter().enumerate() {
+        let is_last = i == nodes.len() - 1;
+
+        lines.push(format!(
+            "{}{}{}{}",
+            prefix,
+            if is_last { "`-- " } else { "|-- " },
+            node.name,
+            if node.is_repeated { " (see above)" } else { "" }
+        ));
+        add_tree_lines(
+            &node.children,
+            &format!("{}{}", prefix, if is_last { "    " } else { "|   " }),
+            lines,
+        );
+    }
+}
+
+/// Warns about installed packages that depend on packages about to be uninstalled through a
+/// package manager, leaving out the ones uninstalled along with them, and prompts to proceed when
+/// there are any, unless asked not to.
+///
+/// Returns whether to proceed with uninstalling. Packages in containers and alternate roots are not
+/// looked into.
+fn confirm_dependents(
+    package_manager: &PackageManager,
+    packages: &[&str],
+    assume_yes: bool,
+    options: &Options,
+) -> bool {
+    if package_manager.rdeps_command.is_empty()
+        || options.container.is_some()
+        || options.root.is_some()
+    {
+        return true;
+    }
+    if let Some(tool) = get_missing_tool(package_manager.rdeps_command) {
+        println!(
+            "{} {}",
+            Color::Yellow.paint("Packages that depend on these could not be looked up without"),
+            Color::Yellow.paint(tool)
+        );
+        return true;
+    }
+
+    let mut has_dependents = false;
+    packages.iter().for_each(|p| {
+        let dependents = get_related_packages(package_manager, p, true)
+            .into_iter()
+            .filter(|d| !packages.contains(&d.as_str()))
+            .collect::<Vec<String>>();
+
+        if !dependents.is_empty() {
+            has_dependents = true;
+            println!(
+                "{} {} {}",
+                Color::Yellow.paint(*p),
+                Color::Yellow.paint("is needed by"),
+                dependents.join(", ")
+            );
+        }
+    });
+
+    if !has_dependents || assume_yes || options.dry_run {
+        return true;
+    }
+
+    println!("Uninstall anyway?");
+    prompt_for_value_from_list(&vec![String::from("Proceed"), String::from("Cancel")]) == "Proceed"
+}
+
+/// Gets the tool a command runs first, when it is not available, like `pactree` that comes with
+/// pacman-contrib.
+fn get_missing_tool(command: &str) -> Option<&str> {
+    command.split_whitespace().next().filter(|t| !does_exist(t))
+}
+
+/// Prints updates pending through the supplied package managers, without applying them.
+///
+/// Package managers whose updates are listed through a tool that is not available, like
+/// `checkupdates` that comes with pacman-contrib, are reported instead. Returns the number of
+/// pending updates, or an error when updates through some package manager could not be checked.
+pub fn outdated(package_managers: &[PackageManager]) -> Result<usize, String> {
+    let package_managers = package_managers
+        .iter()
+        .filter(|p| !p.outdated_command.is_empty()) // Filter out package managers that can't list updates
+        .filter(|p| does_exist(p.command_name))
+        .collect::<Vec<&PackageManager>>();
+
+    let unchecked = package_managers
+        .iter()
+        .filter_map(|p| get_missing_tool(p.outdated_command).map(|t| (p.command_name, t)))
+        .collect::<Vec<(&str, &str)>>();
+    let updates = package_managers
+        .iter()
+        .filter(|p| !unchecked.iter().any(|(c, _)| *c == p.command_name))
+        .flat_map(|p| {
+            run_command_and_get_list(p.outdated_command)
+                .iter()
+                .filter_map(|l| p.parse_update(l))
+                .map(|u| (p.command_name, u))
+                .collect::<Vec<(&str, Update)>>()
+        })
+        .collect::<Vec<(&str, Update)>>();
+
+    if !updates.is_empty() {
+        print_list(&get_updates_table(&updates));
+    } else if unchecked.is_empty() {
+        println!("{}", Color::Blue.paint("Everything is up to date!"));
+    }
+
+    if unchecked.is_empty() {
+        Ok(updates.len())
+    } else {
+        Err(unchecked
+            .iter()
+            .map(|(c, t)| format!("{} needs {}", c, t))
+            .collect::<Vec<String>>()
+            .join(", "))
+    }
+}
+
+/// Generates a table of pending updates, one row for each package.
+fn get_updates_table(updates: &[(&str, Update)]) -> Vec<String> {
+    let rows = std::iter::once(vec![
+        String::from("Source"),
+        String::from("Package"),
+        String::from("Current"),
+        String::from("Available"),
+    ])
+    .chain(updates.iter().map(|(p, u)| {
+        vec![
+            p.to_string(),
+            u.name.to_string(),
+            if u.current_version.is_empty() {
+                String::from("-")
+            } else {
+                u.current_version.to_string()
+            },
+            u.new_version.to_string(),
+        ]
+    }))
+    .collect::<Vec<Vec<String>>>();
+
+    get_aligned_rows(&rows)
+}
+
+/// Prints packages that provide a command or a file through the supplied package managers, along
+/// with a way to install one.
+pub fn provides(package_managers: &[PackageManager], command_or_path: &str) {
+    let path = get_provided_path(command_or_path);
+
+    let providers = package_managers
+        .iter()
+        .filter(|p| !p.provides_command.is_empty()) // Filter out package managers that can't find providers
+        .filter(|p| does_exist(p.command_name))
+        .map(|p| {
+            (
+                p.command_name,
+                get_provider_names(&run_command_and_get_list(&p.gen_provides_command(&path))),
+            )
+        })
+        .filter(|(_, names)| !names.is_empty())
+        .collect::<Vec<(&str, Vec<String>)>>();
+
+    if providers.is_empty() {
+        println!(
+            "{} {}",
+            Color::Red.paint("There was no package found to provide"),
+            Color::Yellow.paint(command_or_path)
+        );
+        return;
+    }
+
+    println!("{} is provided by:", Color::Yellow.paint(command_or_path));
+    providers
+        .iter()
+        .for_each(|(p, names)| print_list(&get_paired_search_results(p, names)));
+    println!(
+        "Install it with: {}",
+        Color::Blue.paint(format!("foraget install {}", providers[0].1[0]))
+    );
+}
+
+/// Gets the path of a file to find providers for, looking up commands in `/usr/bin`.
+fn get_provided_path(command_or_path: &str) -> String {
+    if command_or_path.contains('/') {
+        command_or_path.to_string()
+    } else {
+        format!("/usr/bin/{}", command_or_path)
+    }
+}
+
+/// Extracts unique package names from a list of providers, dropping repository prefixes.
+fn get_provider_names(lines: &[String]) -> Vec<String> {
+    let mut names = Vec::<String>::new();
+
+    lines
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .filter_map(|l| l.rsplit('/').next())
+        .for_each(|n| {
+            if !names.iter().any(|m| m == n) {
+                names.push(n.to_string());
+            }
+        });
+
+    names
+}
+
+/// Prints the packages that own a file on disk, along with the package managers that installed
+/// them.
+///
+/// On systems with a merged `/usr`, a file under `/usr` is also looked up by the path outside it
+/// that packages may have installed it to. Files that do not exist are reported as such.
+pub fn owns(package_managers: &[PackageManager], file: &str) {
+    let path = match env::current_dir() {
+        Ok(directory) => directory.join(file).to_string_lossy().to_string(),
+        Err(_) => file.to_string(),
+    };
+
+    // Look up dangling symbolic links too, as packages can own those
+    if fs::symlink_metadata(&path).is_err() {
+        println!(
+            "{} {}",
+            Color::Yellow.paint(path),
+            Color::Red.paint("does not exist")
+        );
+        return;
+    }
+
+    let owners = get_owned_paths(&path)
+        .iter()
+        .map(|path| {
+            package_managers
+                .iter()
+                .filter(|p| !p.owner_command.is_empty()) // Filter out package managers that can't find owners
+                .filter(|p| does_exist(p.command_name))
+                .flat_map(|p| {
+                    get_paired_search_results(
+                        p.command_name,
+                        &get_provider_names(&run_command_and_get_list(&p.gen_owner_command(path))),
+                    )
+                })
+                .collect::<Vec<String>>()
+        })
+        .find(|owners| !owners.is_empty());
+
+    match owners {
+        Some(owners) => print_list(&owners),
+        None => println!(
+            "{} {}",
+            Color::Yellow.paint(path),
+            Color::Red.paint("is not owned by any package")
+        ),
+    }
+}
+
+/// Gets the paths that packages may have installed a file to, in the order to look them up.
+fn get_owned_paths(path: &str) -> Vec<String> {
+    match path.strip_prefix("/usr") {
+        Some(unmerged_path) if unmerged_path.starts_with('/') => {
+            vec![path.to_string(), unmerged_path.to_string()]
+        }
+        _ => vec![path.to_string()],
+    }
+}
+
+/// Prints operations recorded in the journal that match the supplied filters, oldest first.
+///
+/// Only the most recent entries are printed when a limit is supplied.
+pub fn history(
+    package: Option<&str>,
+    backend: Option<&str>,
+    operation: Option<Operation>,
+    limit: Option<usize>,
+    show_commands: bool,
+) {
+    let entries = match read_entries() {
+        Ok(e) => e,
+        Err(e) => {
+            println!(
+                "{} {}",
+                Color::Red.paint("The journal could not be read:"),
+                e
+            );
+            return;
+        }
+    };
+
+    let entries = entries
+        .iter()
+        .filter(|e| e.matches(package, backend, operation))
+        .collect::<Vec<&Entry>>();
+    let entries = &entries[entries.len() - limit.unwrap_or(entries.len()).min(entries.len())..];
+
+    if entries.is_empty() {
+        println!(
+            "{}",
+            Color::Yellow.paint("There are no recorded operations.")
+        );
+        return;
+    }
+
+    print_list(&get_history_table(entries, show_commands));
+}
+
+/// Generates a table of operations recorded in the journal, one row for each operation.
+fn get_history_table(entries: &[&Entry], show_commands: bool) -> Vec<String> {
+    let or_dash = |value: &str| {
+        if value.is_empty() {
+            String::from("-")
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut header = vec![
+        "ID",
+        "Date (UTC)",
+        "User",
+        "Operation",
+        "Source",
+        "Package",
+        "Version",
+        "Status",
+    ];
+    if show_commands {
+        header.push("Command");
+    }
+
+    let rows = std::iter::once(header.iter().map(|h| h.to_string()).collect())
+        .chain(entries.iter().map(|e| {
+            let mut row = vec![
+                e.id.to_string(),
+                format_timestamp(e.timestamp),
+                e.user.to_string(),
+                e.operation.get_name().to_string(),
+                e.backend.to_string(),
+                if e.package.is_empty() {
+                    String::from("*")
+                } else {
+                    e.package.to_string()
+                },
+                if e.version_before.is_empty() && e.version_after.is_empty() {
+                    String::from("-")
+                } else {
+                    format!(
+                        "{} -> {}",
+                        or_dash(&e.version_before),
+                        or_dash(&e.version_after)
+                    )
+                },
+                if e.status == 0 {
+                    String::from("ok")
+                } else {
+                    format!("failed ({})", e.status)
+                },
+            ];
+            if show_commands {
+                row.push(e.command.to_string());
+            }
+            row
+        }))
+        .collect::<Vec<Vec<String>>>();
+
+    get_aligned_rows(&rows)
+}
+
+/// Reverses an operation recorded in the journal, the most recent successful one by default.
+///
+/// Operations that were already undone, and the reversals themselves, are passed over when looking
+/// for the most recent one.
+///
+/// Reports when the operation cannot be reversed, or can only be reversed in part.
+pub fn undo(package_managers: &[PackageManager], id: Option<u64>, options: &Options) {
+    let entries = match read_entries() {
+        Ok(e) => e,
+        Err(e) => {
+            println!(
+                "{} {}",
+                Color::Red.paint("The journal could not be read:"),
+                e
+            );
+            return;
+        }
+    };
+
+    let get_reversal_id = |entry: &Entry| {
+        entries
+            .iter()
+            .find(|e| e.status == 0 && e.reverses == Some(entry.id))
+            .map(|e| e.id)
+    };
+    let entry = match id {
+        Some(id) => entries.iter().find(|e| e.id == id),
+        None => entries
+            .iter()
+            .rev()
+            .find(|e| e.status == 0 && e.reverses.is_none() && get_reversal_id(e).is_none()),
+    };
+    let entry = match entry {
+        Some(e) if e.status != 0 => {
+            println!(
+                "{} {}",
+                Color::Red.paint("There is nothing to undo, as this operation failed:"),
+                e.id
+            );
+            return;
+        }
+        Some(e) => match get_reversal_id(e) {
+            Some(reversal_id) => {
+                println!(
+                    "{} {}",
+                    Color::Red.paint("This operation was already undone by operation"),
+                    reversal_id
+                );
+                return;
+            }
+            None => e,
+        },
+        None => {
+            println!(
+                "{}",
+                Color::Red.paint("There is no such operation to undo!")
+            );
+            return;
+        }
+    };
+
+    let package = if entry.package.is_empty() {
+        "every package"
+    } else {
+        &entry.package
+    };
+    println!(
+        "Undoing {} of {} via {}...",
+        entry.operation.get_name(),
+        Color::Yellow.paint(package),
+        entry.backend
+    );
+
+    let package_manager = match package_managers
+        .iter()
+        .find(|p| p.command_name == entry.backend)
+    {
+        Some(p) => p,
+        None => {
+            println!(
+                "{} {}",
+                Color::Red.paint("The operation cannot be reversed, as this package manager is not available here:"),
+                Color::Yellow.paint(&entry.backend)
+            );
+            return;
+        }
+    };
+
+    let is_latest = entries
+        .iter()
+        .rev()
+        .find(|e| e.backend == entry.backend && e.status == 0)
+        .is_some_and(|e| e.id == entry.id);
+    let (operation, command, note) =
+        match get_reversal(package_manager, entry, is_latest).and_then(|(o, c, n)| {
+            Ok((
+                o,
+                resolve_remote(package_manager, c, &entry.package, None)?,
+                n,
+            ))
+        }) {
+            Ok(r) => r,
+            Err(e) => {
+                println!(
+                    "{} {}",
+                    Color::Red.paint("The operation cannot be reversed:"),
+                    e
+                );
+                return;
+            }
+        };
+
+    let packages = match entry.package.as_str() {
+        "" => vec![],
+        package => vec![package],
+    };
+    match elevate(package_manager, command, options).and_then(|c| {
+        run_and_record_with(
+            operation,
+            package_manager,
+            &packages,
+            &c,
+            Some(entry.id),
+            options,
+        )
+    }) {
+        Ok(_) => {
+            println!("{}", Color::Blue.paint("Operation complete!"));
+            if let Some(note) = note {
+                println!(
+                    "{} {}",
+                    Color::Yellow.paint("The operation was only reversed in part:"),
+                    note
+                );
+            }
+            warn_about_reboot(package_manager, options);
+        }
+        Err(e) => println!(
+            "{} {}",
+            Color::Red.paint("There was an error undoing the operation:"),
+            e
+        ),
+    }
+}
+
+/// Gets the operation and the command that reverse a recorded operation, along with a note when
+/// they reverse it only in part.
+///
+/// Operations on every package are reversed by undoing the transaction recorded for them, or, for
+/// package managers that can only roll back their last change, only when they are the latest
+/// operation through it.
+///
+/// Returns an error when the operation cannot be reversed through the package manager.
+fn get_reversal(
+    package_manager: &PackageManager,
+    entry: &Entry,
+    is_latest: bool,
+) -> Result<(Operation, String, Option<String>), String> {
+    let package = entry.package.as_str();
+    let version = entry.version_before.as_str();
+    let can_install_version = !package_manager.install_version_command.is_empty();
+
+    match entry.operation {
+        _ if package.is_empty() => {
+            if package_manager.undo_transaction_command.is_empty() {
+                Err(format!(
+                    "{} cannot reverse changes to every package",
+                    package_manager.command_name
+                ))
+            } else if package_manager.undo_transaction_command.contains("{}") {
+                if entry.transaction.is_empty() {
+                    Err(String::from(
+                        "the transaction it was carried out in was not recorded",
+                    ))
+                } else {
+                    Ok((
+                        entry.operation,
+                        package_manager.gen_undo_transaction_command(&entry.transaction),
+                        None,
+                    ))
+                }
+            } else if is_latest {
+                Ok((
+                    entry.operation,
+                    package_manager.undo_transaction_command.to_string(),
+                    None,
+                ))
+            } else {
+                Err(format!(
+                    "{} can only roll back its latest change, and there were more after this one",
+                    package_manager.command_name
+                ))
+            }
+        }
+        Operation::Install if version.is_empty() => Ok((
+            Operation::Uninstall,
+            package_manager.gen_uninstall_command(package),
+            None,
+        )),
+        Operation::Uninstall if version.is_empty() || !can_install_version => Ok((
+            Operation::Install,
+            package_manager.gen_install_command(package),
+            if version.is_empty() {
+                None
+            } else {
+                Some(format!(
+                    "the latest version was installed, as {} cannot install {}",
+                    package_manager.command_name, version
+                ))
+            },
+        )),
+        Operation::Uninstall => Ok((
+            Operation::Install,
+            package_manager.gen_install_version_command(package, version),
+            None,
+        )),
+        _ if version == entry.version_after => {
+            Err(format!("the version of {} did not change", package))
+        }
+        _ if !can_install_version => Err(format!(
+            "{} cannot install {} of {}",
+            package_manager.command_name, version, package
+        )),
+        _ => Ok((
+            Operation::Install,
+            package_manager.gen_install_version_command(package, version),
+            None,
+        )),
+    }
+}
+
+/// Prints packages installed for the supplied reason, grouped by the package managers that
+/// installed them.
+///
+/// When a package manager is supplied, only packages installed through it are printed.
+pub fn list(package_managers: &[PackageManager], reason: &InstallReason, backend: Option<&str>) {
+    let package_managers = package_managers
+        .iter()
+        .filter(|p| backend.is_none() || backend == Some(p.command_name))
+        .collect::<Vec<&PackageManager>>();
+
+    if package_managers.is_empty() {
+        println!(
+            "{} {}",
+            Color::Red.paint("There is no package manager for this system named"),
+            Color::Yellow.paint(backend.unwrap_or_default())
+        );
+        return;
+    }
+
+    get_installed_packages(&package_managers, reason)
+        .iter()
+        .for_each(|(p, packages)| {
+            println!("{} ({})", Color::Blue.paint(p.command_name), packages.len());
+            packages
+                .iter()
+                .for_each(|package| println!("  {}", package));
+        });
+
+    warn_about_removed_packages(&package_managers);
+}
+
+/// Warns about packages installed through foraget via the supplied package managers that were
+/// since removed outside it.
+fn warn_about_removed_packages(package_managers: &[&PackageManager]) {
+    let records = match read_records() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let removed = records
+        .packages
+        .iter()
+        .filter(|r| {
+            package_managers
+                .iter()
+                .filter(|p| does_exist(p.command_name))
+                .any(|p| p.command_name == r.backend && is_removed(p, &r.identifier))
+        })
+        .map(|r| format!("  {} ({})", r.identifier, r.backend))
+        .collect::<Vec<String>>();
+
+    if !removed.is_empty() {
+        println!(
+            "{}",
+            Color::Yellow.paint("Installed through foraget, but since removed outside it:")
+        );
+        print_list(&removed);
+    }
+}
+
+/// Exports packages installed explicitly through the supplied package managers, to a file or to
+/// the standard output.
+pub fn export(
+    package_managers: &[PackageManager],
+    distribution: &Option<Distribution>,
+    format: &ExportFormat,
+    output: Option<&str>,
+    options: &Options,
+) {
+    let installed = get_installed_packages(
+        &package_managers.iter().collect::<Vec<&PackageManager>>(),
+        &InstallReason::Explicit,
+    );
+
+    let casks = if *format == ExportFormat::Brewfile && does_exist("brew") {
+        run_command_and_get_list(CASKS_COMMAND)
+    } else {
+        vec![]
+    };
+
+    let text = match gen_export(&installed, &casks, format, &options.aliases, distribution) {
+        Ok(t) => t,
+        Err(e) => {
+            println!("{}", Color::Red.paint(e));
+            return;
+        }
+    };
+
+    match output {
+        Some(path) => match fs::write(path, text) {
+            Ok(_) => println!(
+                "{} {}",
+                Color::Blue.paint("Exported installed packages to"),
+                Color::Yellow.paint(path)
+            ),
+            Err(e) => println!(
+                "{} {} ({})",
+                Color::Red.paint("There was an error writing to"),
+                Color::Yellow.paint(path),
+                e
+            ),
+        },
+        None => print!("{}", text),
+    }
+}
+
+/// Gets packages installed for the supplied reason, paired with the package managers that
+/// installed them.
+fn get_installed_packages<'a>(
+    package_managers: &[&'a PackageManager],
+    reason: &InstallReason,
+) -> Vec<(&'a PackageManager, Vec<String>)> {
+    package_managers
+        .iter()
+        .filter(|p| !p.get_list_commands(reason).is_empty()) // Filter out package managers that can't list packages
+        .filter(|p| does_exist(p.command_name))
+        .map(|p| {
+            (
+                *p,
+                p.get_list_commands(reason)
+                    .iter()
+                    .flat_map(|c| run_command_and_get_list(c))
+                    .filter(|l| !l.is_empty())
+                    .collect::<Vec<String>>(),
+            )
+        })
+        .collect()
+}
+
+/// Uninstalls packages through the package managers they are installed through, in one go through
+/// each package manager, and prints a summary of the outcome for each of them.
+///
+/// Prompts to choose one when a package is installed through more than one package manager, and to
+/// proceed when other packages depend on the ones to uninstall, unless asked not to.
+pub fn uninstall(
+    package_managers: &[PackageManager],
+    packages_to_uninstall: &[&str],
+    assume_yes: bool,
+    options: &Options,
+) {
+    warn_about_unsupported_root(package_managers, options);
+
+    let package_managers = package_managers
+        .iter()
+        .filter(|p| !p.uninstall_key.is_empty()) // Filter out package managers that can't uninstall
+        .filter(|p| options.root.is_none() || !p.root_key.is_empty()) // Filter out package managers that can't target the root
+        .filter(|p| does_exist(p.command_name))
+        .collect::<Vec<&PackageManager>>();
+
+    let mut outcomes = vec![];
+    let mut selected = vec![];
+    for package in packages_to_uninstall {
+        match choose_installed_package(&package_managers, package, options) {
+            Some((p, identifier)) => {
+                selected.push((p.command_name, (p, package.to_string(), identifier)))
+            }
+            None => outcomes.push((package.to_string(), Err(String::from("not installed")))),
+        }
+    }
+
+    for (backend, packages) in group_by_backend(selected) {
+        let identifiers = packages
+            .iter()
+            .map(|(_, _, i)| i.as_str())
+            .collect::<Vec<&str>>();
+        let outcome = uninstall_through(packages[0].0, &identifiers, assume_yes, options);
+
+        packages.iter().for_each(|(_, name, _)| {
+            outcomes.push((
+                name.to_string(),
+                outcome
+                    .as_ref()
+                    .map(|_| format!("uninstalled via {}", backend))
+                    .map_err(|e| e.to_string()),
+            ))
+        });
+    }
+
+    print_summary(&outcomes);
+}
+
+/// Uninstalls packages in one go through the supplied package manager, and forgets them.
+fn uninstall_through(
+    package_manager: &PackageManager,
+    packages: &[&str],
+    assume_yes: bool,
+    options: &Options,
+) -> Result<(), String> {
+    if !confirm_dependents(package_manager, packages, assume_yes, options) {
+        return Err(String::from("cancelled, as other packages need it"));
+    }
+    println!(
+        "Uninstalling {} via {}...",
+        Color::Yellow.paint(packages.join(", ")),
+        package_manager.command_name
+    );
+
+    run_and_record(
+        Operation::Uninstall,
+        package_manager,
+        packages,
+        &elevate(
+            package_manager,
+            package_manager.gen_uninstall_packages_command(packages),
+            options,
+        )?,
+        options,
+    )?;
+    packages
+        .iter()
+        .for_each(|p| forget_package(package_manager, p, options));
+
+    Ok(())
+}
+
+/// Runs an installed package with the supplied arguments, through the package manager it is
+/// installed through, and returns its exit code.
+///
+/// Prompts to choose one when the package is installed through more than one package manager.
+pub fn run(
+    package_managers: &[PackageManager],
+    package_to_run: &str,
+    arguments: &[&str],
+    options: &Options,
+) -> i32 {
+    let package_managers = package_managers
+        .iter()
+        .filter(|p| does_exist(p.command_name))
+        .collect::<Vec<&PackageManager>>();
+
+    let (package_manager, package) =
+        match choose_installed_package(&package_managers, package_to_run, options) {
+            Some(p) => p,
+            None => return 1,
+        };
+
+    let command = std::iter::once(package_manager.gen_run_command(&package))
+        .chain(arguments.iter().map(|a| quote(a)))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    if options.dry_run {
+        show_command(&command);
+        return 0;
+    }
+
+    match run_command_interactive(&command) {
+        Ok(code) => code,
+        Err(e) => {
+            println!(
+                "{} {} ({})",
+                Color::Red.paint("There was an error running"),
+                Color::Yellow.paint(package_to_run),
+                e
+            );
+            1
+        }
+    }
+}
+
+/// Chooses the package manager to act on an installed package through, along with the identifier
+/// of the package there.
+///
+/// Prompts to choose one when the package is installed through more than one package manager, and
+/// returns `None` when it is installed through none.
+fn choose_installed_package<'a>(
+    package_managers: &[&'a PackageManager],
+    package: &str,
+    options: &Options,
+) -> Option<(&'a PackageManager, String)> {
+    let installed = get_installed_through(package_managers, package, options);
+
+    if installed.is_empty() {
+        println!(
+            "{} {}",
+            Color::Yellow.paint(package),
+            Color::Red.paint("is not installed through an available package manager")
+        );
+        return None;
+    }
+
+    choose_package_manager(installed)
+}
+
+/// Chooses one of the supplied pairs of package managers and packages.
+///
+/// Prompts to choose one when there is more than one.
+fn choose_package_manager(
+    mut candidates: Vec<(&PackageManager, String)>,
+) -> Option<(&PackageManager, String)> {
+    if candidates.len() < 2 {
+        return candidates.pop();
+    }
+
+    // Let user choose one of the package managers
+    let selected = prompt_for_value_from_list(
+        &candidates
+            .iter()
+            .map(|(p, _)| p.command_name.to_string())
+            .collect::<Vec<String>>(),
+    );
+
+    candidates
+        .into_iter()
+        .find(|(p, _)| p.command_name == selected)
+}
+
+/// Gets the package managers a package is installed through, along with the identifier of the
+/// package for each of them.
+///
+/// Package managers remembered for the package when it was installed through foraget are trusted,
+/// and the others are only looked through when there are none. Packages that were since removed
+/// outside foraget are reported and forgotten.
+fn get_installed_through<'a>(
+    package_managers: &[&'a PackageManager],
+    package: &str,
+    options: &Options,
+) -> Vec<(&'a PackageManager, String)> {
+    let remembered = get_remembered_packages(package_managers, package, options)
+        .into_iter()
+        .filter(|(p, identifier)| {
+            if !is_removed(p, identifier) {
+                return true;
+            }
+
+            println!(
+                "{} was removed from {} outside foraget, and is no longer remembered",
+                Color::Yellow.paint(identifier),
+                p.command_name
+            );
+            forget_package(p, identifier, options);
+            false
+        })
+        .collect::<Vec<(&PackageManager, String)>>();
+
+    if !remembered.is_empty() {
+        return remembered;
+    }
+
+    package_managers
+        .iter()
+        .filter(|p| match &options.root {
+            Some(root) => is_installed_in_root(p, package, root),
+            None => is_installed(p, package),
+        })
+        .map(|p| (*p, package.to_string()))
+        .collect()
+}
+
+/// Returns whether a package is installed through a package manager in an alternate root.
+///
+/// Packages are assumed to be installed when the package manager cannot look them up there, like
+/// apt that looks them up through `dpkg-query`, leaving it to the package manager to refuse.
+fn is_installed_in_root(package_manager: &PackageManager, package: &str, root: &str) -> bool {
+    match package_manager.gen_rooted_command(
+        &package_manager.gen_installed_version_command(package),
+        root,
+    ) {
+        Ok(command) if !package_manager.installed_version_command.is_empty() => {
+            !run_command_and_get_list(&command)[0].is_empty()
+        }
+        _ => true,
+    }
+}
+
+/// Gets the supplied package managers that a package was installed through by foraget, along with
+/// the identifier of the package for each of them.
+///
+/// Packages are only remembered on the host system.
+fn get_remembered_packages<'a>(
+    package_managers: &[&'a PackageManager],
+    package: &str,
+    options: &Options,
+) -> Vec<(&'a PackageManager, String)> {
+    if options.container.is_some() || options.root.is_some() {
+        return vec![];
+    }
+
+    match read_records() {
+        Ok(records) => records
+            .find(package)
+            .into_iter()
+            .filter_map(|r| {
+                package_managers
+                    .iter()
+                    .find(|p| p.command_name == r.backend)
+                    .map(|p| (*p, r.identifier.to_string()))
+            })
+            .collect(),
+        Err(e) => {
+            println!(
+                "{} {}",
+                Color::Yellow.paint("Ignoring installed packages that could not be read from"),
+                e
+            );
+            vec![]
+        }
+    }
+}
+
+/// Returns whether a package remembered for a package manager is no longer installed through it.
+///
+/// Packages are assumed to remain when the package manager cannot tell.
+fn is_removed(package_manager: &PackageManager, identifier: &str) -> bool {
+    !package_manager.installed_version_command.is_empty()
+        && !is_installed(package_manager, identifier)
+}
+
+/// Remembers the package manager a package was installed through, along with its identifier
+/// there.
+///
+/// Packages are only remembered on the host system.
+fn remember_package(
+    package_manager: &PackageManager,
+    name: &str,
+    identifier: &str,
+    options: &Options,
+) {
+    if options.container.is_some() || options.root.is_some() || options.dry_run {
+        return;
+    }
+
+    let record = Record::new(name, package_manager.command_name, identifier);
+    if let Err(e) = update_records(|r| r.add(record)) {
+        println!(
+            "{} {}",
+            Color::Yellow.paint("The installed package could not be remembered:"),
+            e
+        );
+    }
+}
+
+/// Forgets a package installed through a package manager.
+fn forget_package(package_manager: &PackageManager, identifier: &str, options: &Options) {
+    if options.container.is_some() || options.root.is_some() || options.dry_run {
+        return;
+    }
+
+    if let Err(e) = update_records(|r| r.remove(package_manager.command_name, identifier)) {
+        println!(
+            "{} {}",
+            Color::Yellow.paint("The uninstalled package could not be forgotten:"),
+            e
+        );
+    }
+}
+
+/// Brings the system in line with a manifest, installing declared packages that are missing.
+///
+/// Packages installed explicitly through the package managers that the manifest uses, but not
+/// declared in it, are reported and removed when pruning, after a preview and a prompt to proceed
+/// unless asked not to. Packages that make up the base system are left alone. Nothing is changed
+/// when only checking.
+#[allow(clippy::too_many_arguments)]
+pub fn sync(
+    package_managers: &[PackageManager],
+    distribution: &Option<Distribution>,
+    manifest: &Manifest,
+    groups: &[&str],
+    prune: bool,
+    check: bool,
+    assume_yes: bool,
+    options: &Options,
+) {
+    let mut package_managers = package_managers
+        .iter()
+        .filter(|p| does_exist(p.command_name))
+        .collect::<Vec<&PackageManager>>();
+    package_managers.sort_by_key(|p| p.distributions.is_empty());
+
+    // Packages from every group count as declared, so that syncing a group prunes nothing else
+    let declared = manifest
+        .packages
+        .iter()
+        .flat_map(|m| get_candidates(&package_managers, m, distribution, &options.aliases))
+        .map(|(p, name)| (p.command_name, name.to_string()))
+        .collect::<Vec<(&str, String)>>();
+    let mut used = vec![];
+    let mut outcomes = vec![];
+
+    for package in manifest.get_packages(groups) {
+        let candidates = get_candidates(&package_managers, package, distribution, &options.aliases);
+
+        if let Some((p, _)) = candidates.iter().find(|(p, name)| is_installed(p, name)) {
+            used.push(p.command_name);
+            continue;
+        }
+
+        let outcome = if candidates.is_empty() {
+            Err(format!(
+                "{} is not available",
+                package.backend.as_deref().unwrap_or_default()
+            ))
+        } else if check {
+            Err(String::from("missing"))
+        } else {
+            install_declared_package(
+                &package.name,
+                &candidates,
+                package.backend.is_some(),
+                options,
+            )
+        };
+
+        if let Ok(p) = outcome {
+            used.push(p);
+        }
+        outcomes.push((
+            package.name.to_string(),
+            outcome.map(|p| format!("installed via {}", p)),
+        ));
+    }
+
+    used.dedup();
+    let used = package_managers
+        .into_iter()
+        .filter(|p| used.contains(&p.command_name))
+        .collect::<Vec<&PackageManager>>();
+    let undeclared = get_installed_packages(&used, &InstallReason::Explicit)
+        .into_iter()
+        .map(|(p, packages)| {
+            let base = run_command_and_get_list(p.list_base_command);
+            let packages = packages
+                .into_iter()
+                .filter(|n| !declared.contains(&(p.command_name, n.to_string())))
+                .filter(|n| !base.contains(n))
+                .collect::<Vec<String>>();
+
+            (p, packages)
+        })
+        .filter(|(_, packages)| !packages.is_empty())
+        .collect::<Vec<(&PackageManager, Vec<String>)>>();
+
+    let mut should_prune = prune && !check && !undeclared.is_empty();
+    if should_prune {
+        undeclared.iter().for_each(|(p, packages)| {
+            println!(
+                "Packages that would be removed via {}:",
+                Color::Blue.paint(p.command_name)
+            );
+            print_list(
+                &packages
+                    .iter()
+                    .map(|n| format!("  {}", n))
+                    .collect::<Vec<String>>(),
+            );
+        });
+        should_prune = assume_yes || options.dry_run || confirm_removal();
+    }
+
+    for (p, packages) in undeclared {
+        packages.into_iter().for_each(|n| {
+            let outcome = if should_prune {
+                uninstall_through(p, &[&n], assume_yes, options)
+                    .map(|_| format!("removed via {}", p.command_name))
+            } else {
+                Err(format!(
+                    "not in the manifest, installed via {}",
+                    p.command_name
+                ))
+            };
+
+            outcomes.push((n, outcome));
+        });
+    }
+
+    if outcomes.is_empty() {
+        println!("{}", Color::Blue.paint("Everything is in sync!"));
+        return;
+    }
+
+    print_summary(&outcomes);
+}
+
+/// Gets the package managers a declared package can be installed through, along with its name
+/// for each of them.
+fn get_candidates<'a>(
+    package_managers: &[&'a PackageManager],
+    package: &'a ManifestPackage,
+    distribution: &Option<Distribution>,
+    aliases: &'a Aliases,
+) -> Vec<(&'a PackageManager, &'a str)> {
+    package_managers
+        .iter()
+        .filter(|p| {
+            package
+                .backend
+                .as_ref()
+                .map_or(true, |b| b == p.command_name)
+        })
+        .map(|p| (*p, package.get_name(distribution, p.command_name, aliases)))
+        .collect()
+}
+
+/// Installs a declared package through the first of the supplied package managers that offers it.
+///
+/// A package manager preferred by the manifest is trusted to offer the package.
+fn install_declared_package(
+    name: &str,
+    candidates: &[(&PackageManager, &str)],
+    is_preferred: bool,
+    options: &Options,
+) -> Result<&'static str, String> {
+    let (package_manager, package) = candidates
+        .iter()
+        .find(|(p, name)| {
+            is_preferred
+                || (!p.info_key.is_empty()
+                    && !p
+                        .parse_info(&run_command_and_get_list(&p.gen_info_command(name)))
+                        .is_empty())
+        })
+        .ok_or_else(|| String::from("not found"))?;
+
+    println!(
+        "Installing {} via {}...",
+        Color::Yellow.paint(*package),
+        package_manager.command_name
+    );
+
+    let command = apply_install_options(
+        package_manager,
+        package_manager.gen_install_command(package),
+        options,
+    )?;
+    run_and_record(
+        Operation::Install,
+        package_manager,
+        &[package],
+        &command,
+        options,
+    )?;
+    remember_package(package_manager, name, package, options);
+    warn_about_reboot(package_manager, options);
+
+    Ok(package_manager.command_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package_managers::get_known_package_managers_for_linux;
+
+    #[test]
+    fn get_paired_search_results_some_values() {
+        assert_eq!(
+            vec![
+                String::from("pack-man -> One"),
+                String::from("pack-man -> Two"),
+                String::from("pack-man -> Three")
+            ],
+            get_paired_search_results(
+                "pack-man",
+                &[
+                    String::from("One"),
+                    String::from("Two"),
+                    String::from("Three")
+                ]
+            ),
+            "Creates a string vector of pairs when supplied with a few values"
+        );
+    }
+
+    #[test]
+    fn get_paired_search_results_no_value() {
+        assert_eq!(
+            Vec::<String>::new(),
+            get_paired_search_results("pack-man", &Vec::<String>::new()),
+            "Creates a string vector of pairs when supplied with no value"
+        );
+    }
+
+    #[test]
+    fn get_info_table_single() {
+        assert_eq!(
+            vec![
+                String::from("Source   pack-man"),
+                String::from("Version  1.0"),
+                String::from("License  -"),
+            ],
+            get_info_table(&[(
+                "pack-man",
+                vec![("Version", String::from("1.0")), ("License", String::new())]
+            )]),
+            "Generates a table of package information from a single package manager"
+        );
+    }
+
+    #[test]
+    fn get_info_table_side_by_side() {
+        assert_eq!(
+            vec![
+                String::from("Source   pack-man  other-man"),
+                String::from("Version  1.0       1.1"),
+                String::from("Depends  -         one, two, three, four, five, six, sev..."),
+            ],
+            get_info_table(&[
+                (
+                    "pack-man",
+                    vec![("Version", String::from("1.0")), ("Depends", String::new())]
+                ),
+                (
+                    "other-man",
+                    vec![
+                        ("Version", String::from("1.1")),
+                        (
+                            "Depends",
+                            String::from("one, two, three, four, five, six, seven, eight")
+                        )
+                    ]
+                ),
+            ]),
+            "Generates a table of package information from package managers side by side"
+        );
+    }
+
+    #[test]
+    fn format_versions_some_values() {
+        let versions = ["8", "7", "6", "5", "4", "3", "2"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            "8, 7, 6",
+            format_versions(&versions[..3]),
+            "Formats a few versions"
+        );
+        assert_eq!(
+            "8, 7, 6, 5, 4 and 2 more",
+            format_versions(&versions),
+            "Formats many versions with a count of the rest"
+        );
+    }
+
+    #[test]
+    fn parse_sized_items_some_values() {
+        assert_eq!(
+            vec![
+                (String::from("libfoo"), Some(2048)),
+                (String::from("/var/cache/apt/archives"), Some(4096)),
+                (String::from("org.kde.Platform"), None),
+            ],
+            parse_sized_items(&[
+                String::from("libfoo 2048"),
+                String::from("/var/cache/apt/archives 4096"),
+                String::from("org.kde.Platform"),
+                String::new(),
+            ]),
+            "Parses items with and without sizes"
+        );
+    }
+
+    #[test]
+    fn format_size_some_values() {
+        assert_eq!("512 B", format_size(512), "Formats bytes");
+        assert_eq!("1.5 KiB", format_size(1536), "Formats kibibytes");
+        assert_eq!(
+            "12.0 MiB",
+            format_size(12 * 1024 * 1024),
+            "Formats mebibytes"
+        );
+    }
+
+    #[test]
+    fn format_total_size_some_values() {
+        assert_eq!(
+            "3.0 KiB",
+            format_total_size(&[
+                (String::from("libfoo"), Some(1024)),
+                (String::from("libbar"), Some(2048)),
+            ]),
+            "Formats the total of known sizes"
+        );
+        assert_eq!(
+            "at least 1.0 KiB",
+            format_total_size(&[
+                (String::from("libfoo"), Some(1024)),
+                (String::from("org.kde.Platform"), None),
+            ]),
+            "Notes that some sizes are unknown"
+        );
+    }
+
+    #[test]
+    fn get_dependency_tree_some_values() {
+        let get_related = |p: &str| match p {
+            "emacs" => vec![String::from("gtk3"), String::from("libxml2")],
+            "gtk3" => vec![String::from("glib2"), String::from("libxml2")],
+            "glib2" => vec![String::from("pcre2")],
+            _ => vec![],
+        };
+
+        assert_eq!(
+            vec![
+                "emacs",
+                "|-- gtk3",
+                "|   |-- glib2",
+                "|   `-- libxml2",
+                "`-- libxml2 (see above)",
+            ],
+            get_tree_lines(&get_dependency_tree("emacs", 2, &mut vec![], &get_related)),
+            "Draws a tree down to the depth, without expanding packages already in it"
+        );
+    }
+
+    #[test]
+    fn get_updates_table_some_values() {
+        assert_eq!(
+            vec![
+                "Source   Package        Current  Available",
+                "pacman   emacs          29.1-1   29.1-2",
+                "flatpak  org.gimp.GIMP  -        2.10.36",
+            ],
+            get_updates_table(&[
+                (
+                    "pacman",
+                    Update {
+                        name: String::from("emacs"),
+                        current_version: String::from("29.1-1"),
+                        new_version: String::from("29.1-2"),
+                    }
+                ),
+                (
+                    "flatpak",
+                    Update {
+                        name: String::from("org.gimp.GIMP"),
+                        current_version: String::new(),
+                        new_version: String::from("2.10.36"),
+                    }
+                ),
+            ]),
+            "Generates a table of pending updates"
+        );
+    }
+
+    #[test]
+    fn get_provided_path_command() {
+        assert_eq!(
+            "/usr/bin/fd",
+            get_provided_path("fd"),
+            "Looks up a command in /usr/bin"
+        );
+    }
+
+    #[test]
+    fn get_provided_path_path() {
+        assert_eq!(
+            "/etc/hosts",
+            get_provided_path("/etc/hosts"),
+            "Keeps a path as it is"
+        );
+    }
+
+    #[test]
+    fn get_provider_names_some_values() {
+        assert_eq!(
+            vec![String::from("fd"), String::from("fd-git")],
+            get_provider_names(&[
+                String::from("extra/fd"),
+                String::from("community/fd"),
+                String::from("fd-git"),
+                String::new(),
+            ]),
+            "Extracts unique package names without repositories"
+        );
+    }
+
+    #[test]
+    fn get_owned_paths_merged_usr() {
+        assert_eq!(
+            vec![String::from("/usr/bin/ls"), String::from("/bin/ls")],
+            get_owned_paths("/usr/bin/ls"),
+            "Looks up a file under /usr outside it as well"
+        );
+    }
+
+    #[test]
+    fn get_owned_paths_elsewhere() {
+        assert_eq!(
+            vec![String::from("/etc/hosts")],
+            get_owned_paths("/etc/hosts"),
+            "Looks up a file outside /usr by its path alone"
+        );
+    }
+
+    #[test]
+    fn get_history_table_some_values() {
+        let mut install = Entry::new(Operation::Install, "pacman", "emacs", "pacman -S emacs");
+        install.id = 1;
+        install.timestamp = 1_700_000_000;
+        install.user = String::from("someone");
+        install.version_after = String::from("29.1-2");
+        let mut upgrade = Entry::new(Operation::Upgrade, "flatpak", "", "flatpak update -y");
+        upgrade.id = 2;
+        upgrade.timestamp = 1_700_000_060;
+        upgrade.user = String::from("someone");
+        upgrade.status = 1;
+
+        assert_eq!(
+            vec![
+                "ID  Date (UTC)        User     Operation  Source   Package  Version      Status",
+                "1   2023-11-14 22:13  someone  install    pacman   emacs    - -> 29.1-2  ok",
+                "2   2023-11-14 22:14  someone  upgrade    flatpak  *        -            failed (1)",
+            ],
+            get_history_table(&[&install, &upgrade], false),
+            "Generates a table of recorded operations"
+        );
+    }
+
+    fn get_entry(operation: Operation, package: &str, before: &str, after: &str) -> Entry {
+        let mut entry = Entry::new(operation, "pacman", package, "");
+        entry.version_before = before.to_string();
+        entry.version_after = after.to_string();
+        entry
+    }
+
+    fn get_package_manager(command_name: &str) -> PackageManager {
+        get_known_package_managers_for_linux()
+            .into_iter()
+            .find(|p| p.command_name == command_name)
+            .unwrap()
+    }
+
+    fn get_options(root: Option<&str>) -> Options {
+        Options {
+            apply_live: false,
+            container: None,
+            export: false,
+            root: root.map(|r| r.to_string()),
+            privilege: Privilege::Root,
+            aliases: Aliases::default(),
+            dry_run: true,
+        }
+    }
+
+    #[test]
+    fn is_local_file_some_values() {
+        let package_managers = get_known_package_managers_for_linux();
+
+        assert!(
+            is_local_file(&package_managers, "emacs_29.1_amd64.deb"),
+            "Takes an argument with the extension of a package file as a file"
+        );
+        assert!(
+            is_local_file(&package_managers, "./Cargo.toml"),
+            "Takes an existing path with a slash as a file"
+        );
+        assert!(
+            !is_local_file(&package_managers, "Cargo.toml"),
+            "Takes an existing file without a slash or a known extension as a package"
+        );
+        assert!(
+            !is_local_file(&package_managers, "hashicorp/tap/terraform"),
+            "Takes a name with slashes that is not a file as a package"
+        );
+    }
+
+    #[test]
+    fn refuse_holds_in_root_some_values() {
+        assert!(
+            refuse_holds_in_root(&get_options(Some("/mnt"))),
+            "Refuses holds in an alternate root"
+        );
+        assert!(
+            !refuse_holds_in_root(&get_options(None)),
+            "Allows holds on the host"
+        );
+    }
+
+    #[test]
+    fn is_held_through_package_manager() {
+        let apt = get_package_manager("apt");
+        let snap = get_package_manager("snap");
+        let held = vec![(&apt, vec![String::from("emacs")])];
+
+        assert!(
+            is_held(&held, &apt, "emacs"),
+            "Finds a package held through a package manager"
+        );
+        assert!(
+            !is_held(&held, &snap, "emacs"),
+            "Ignores packages held through other package managers"
+        );
+        assert!(
+            !is_held(&held, &apt, "vim"),
+            "Ignores packages that are not held"
+        );
+    }
+
+    #[test]
+    fn resolve_pinned_version_unordered() {
+        assert_eq!(
+            Ok(String::from("latest/edge")),
+            resolve_pinned_version(
+                &get_package_manager("snap"),
+                "emacs@latest/edge",
+                "emacs",
+                None
+            ),
+            "Takes an exact channel as it is"
+        );
+        assert!(
+            resolve_pinned_version(&get_package_manager("flatpak"), "emacs@>=29", "emacs", None)
+                .is_err(),
+            "Rejects a range of versions that cannot be ordered"
+        );
+    }
+
+    #[test]
+    fn get_reversal_install() {
+        assert_eq!(
+            Ok((
+                Operation::Uninstall,
+                String::from("pacman -R 'emacs' --noconfirm"),
+                None
+            )),
+            get_reversal(
+                &get_package_manager("pacman"),
+                &get_entry(Operation::Install, "emacs", "", "29.1-2"),
+                true
+            ),
+            "Uninstalls a package that was installed"
+        );
+    }
+
+    #[test]
+    fn get_reversal_install_local_file() {
+        let apt = get_package_manager("apt");
+        let name = apt
+            .get_file_package_name("./emacs_29.1+1-3_amd64.deb")
+            .unwrap();
+        let mut entry = Entry::new(Operation::Install, "apt", &name, "");
+        entry.version_after = String::from("29.1+1-3");
+
+        assert_eq!(
+            Ok((
+                Operation::Uninstall,
+                String::from("apt remove 'emacs' -y"),
+                None
+            )),
+            get_reversal(&apt, &entry, true),
+            "Uninstalls a package that was installed from a local file by its name"
+        );
+    }
+
+    #[test]
+    fn get_reversal_uninstall() {
+        assert_eq!(
+            Ok((
+                Operation::Install,
+                String::from(
+                    "pacman -U --noconfirm /var/cache/pacman/pkg/'emacs'-'29.1-2'-*.pkg.tar.zst"
+                ),
+                None
+            )),
+            get_reversal(
+                &get_package_manager("pacman"),
+                &get_entry(Operation::Uninstall, "emacs", "29.1-2", ""),
+                true
+            ),
+            "Reinstalls the version of a package that was uninstalled"
+        );
+    }
+
+    #[test]
+    fn get_reversal_elevated() {
+        let (_, command, _) = get_reversal(
+            &get_package_manager("dnf"),
+            &get_entry(Operation::Upgrade, "curl", "8.2.1-1", "8.2.1-3"),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Ok(String::from(
+                "sudo sh -c 'dnf install -y '\\''curl'\\''-'\\''8.2.1-1'\\'' || dnf downgrade -y '\\''curl'\\''-'\\''8.2.1-1'\\'''"
+            )),
+            Privilege::Tool(String::from("sudo")).gen_command(&command),
+            "Elevates both the install and the downgrade that reinstall an earlier version"
+        );
+    }
+
+    #[test]
+    fn get_reversal_uninstall_latest() {
+        assert_eq!(
+            Ok((
+                Operation::Install,
+                String::from("paru -S 'emacs-git' --noconfirm"),
+                Some(String::from(
+                    "the latest version was installed, as paru cannot install 29.1"
+                ))
+            )),
+            get_reversal(
+                &get_package_manager("paru"),
+                &get_entry(Operation::Uninstall, "emacs-git", "29.1", ""),
+                true
+            ),
+            "Reinstalls the latest version of a package when the version cannot be installed"
+        );
+    }
+
+    #[test]
+    fn get_reversal_upgrade() {
+        assert_eq!(
+            Ok((
+                Operation::Install,
+                String::from("apt install -y --allow-downgrades 'curl'='7.88.1-10'"),
+                None
+            )),
+            get_reversal(
+                &get_package_manager("apt"),
+                &get_entry(Operation::Upgrade, "curl", "7.88.1-10", "7.88.1-10+deb12u5"),
+                true
+            ),
+            "Reinstalls the version of a package from before it was upgraded"
+        );
+    }
+
+    #[test]
+    fn get_reversal_upgrade_everything() {
+        let mut upgrade = get_entry(Operation::Upgrade, "", "", "");
+        upgrade.transaction = String::from("42");
+
+        assert_eq!(
+            Ok((
+                Operation::Upgrade,
+                String::from("dnf history undo '42' -y"),
+                None
+            )),
+            get_reversal(&get_package_manager("dnf"), &upgrade, false),
+            "Undoes the transaction recorded for an upgrade of every package"
+        );
+        assert!(
+            get_reversal(
+                &get_package_manager("dnf"),
+                &get_entry(Operation::Upgrade, "", "", ""),
+                true
+            )
+            .is_err(),
+            "Cannot undo an upgrade of every package without its transaction"
+        );
+        assert_eq!(
+            Ok((
+                Operation::Upgrade,
+                String::from("rpm-ostree rollback"),
+                None
+            )),
+            get_reversal(&get_package_manager("rpm-ostree"), &upgrade, true),
+            "Rolls back the latest upgrade of every package"
+        );
+        assert!(
+            get_reversal(&get_package_manager("rpm-ostree"), &upgrade, false).is_err(),
+            "Cannot roll back an upgrade of every package that was followed by other changes"
+        );
+        assert!(
+            get_reversal(&get_package_manager("apt"), &upgrade, true).is_err(),
+            "Cannot reverse an upgrade of every package without a way to undo transactions"
+        );
+    }
+
+    #[test]
+    fn break_pair_from_search_result_simple() {
+        assert_eq!(
+            (String::from("pack-man"), String::from("emacs")),
+            break_pair_from_search_result("pack-man -> emacs"),
+            "Breaks a search result into a pair"
+        );
+    }
+
+    #[test]
+    fn get_selection_choices_round_trip() {
+        let choices = get_selection_choices(
+            "emacs",
+            &[
+                String::from("pacman -> emacs"),
+                String::from("flatpak -> org.gnu.emacs"),
+            ],
+        );
+
+        assert_eq!(
+            vec![
+                String::from("emacs: pacman -> emacs"),
+                String::from("emacs: flatpak -> org.gnu.emacs"),
+            ],
+            choices,
+            "Qualifies search results with the name of the package"
+        );
+        assert_eq!(
+            (
+                String::from("emacs"),
+                String::from("flatpak -> org.gnu.emacs")
+            ),
+            break_selection_choice(&choices[1]),
+            "Breaks a choice into the name of the package and the search result"
+        );
+    }
+
+    #[test]
+    fn group_by_backend_some_values() {
+        assert_eq!(
+            vec![
+                ("apt", vec!["emacs", "vim"]),
+                ("flatpak", vec!["org.gnu.emacs"])
+            ],
+            group_by_backend(vec![
+                ("apt", "emacs"),
+                ("flatpak", "org.gnu.emacs"),
+                ("apt", "vim"),
+            ]),
+            "Groups packages by package manager in the order they first appear"
         );
     }
 }
@@ -0,0 +1,308 @@
+//! Contains logic around versions of packages requested with `name@version`.
+
+use std::cmp::Ordering;
+
+/// A constraint on the version of a package.
+#[derive(Debug, PartialEq)]
+enum Constraint {
+    Equal(String),
+    Prefix(String),
+    Less(String),
+    LessOrEqual(String),
+    Greater(String),
+    GreaterOrEqual(String),
+}
+
+impl Constraint {
+    /// Returns whether a version satisfies the constraint.
+    fn matches(&self, version: &str) -> bool {
+        match self {
+            Constraint::Equal(v) => {
+                compare_versions(version, v) == Ordering::Equal
+                    || compare_versions(&strip_release(version, v), v) == Ordering::Equal
+            }
+            Constraint::Prefix(p) => version.starts_with(p.as_str()),
+            Constraint::Less(v) => compare_versions(version, v) == Ordering::Less,
+            Constraint::LessOrEqual(v) => compare_versions(version, v) != Ordering::Greater,
+            Constraint::Greater(v) => compare_versions(version, v) == Ordering::Greater,
+            Constraint::GreaterOrEqual(v) => compare_versions(version, v) != Ordering::Less,
+        }
+    }
+}
+
+/// A requested version of a package, either exact like `1.2.3` or a range like `>=1.2,<2` or
+/// `1.2.*`.
+#[derive(Debug, PartialEq)]
+pub struct VersionSpec {
+    constraints: Vec<Constraint>,
+}
+
+impl VersionSpec {
+    /// Gets the exact version requested, if it is not a range.
+    pub fn get_exact(&self) -> Option<&str> {
+        match self.constraints.as_slice() {
+            [Constraint::Equal(v)] => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns whether a version satisfies every constraint.
+    pub fn matches(&self, version: &str) -> bool {
+        self.constraints.iter().all(|c| c.matches(version))
+    }
+
+    /// Gets the latest of the supplied versions that satisfies the requested one.
+    pub fn resolve<'a>(&self, versions: &'a [String]) -> Option<&'a str> {
+        versions
+            .iter()
+            .filter(|v| self.matches(v))
+            .max_by(|a, b| compare_versions(a, b))
+            .map(|v| v.as_str())
+    }
+}
+
+/// Strips the epoch like `1:` and the release like `-2` off a version, unless the requested one
+/// has them too, so that `13.0.0` can be requested for `13.0.0-2`.
+fn strip_release(version: &str, requested: &str) -> String {
+    let version = match version.split_once(':') {
+        Some((epoch, v))
+            if !requested.contains(':') && epoch.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            v
+        }
+        _ => version,
+    };
+
+    match version.rsplit_once('-') {
+        Some((v, _)) if !requested.contains('-') => v.to_string(),
+        _ => version.to_string(),
+    }
+}
+
+/// Splits a package requested as `name@version` into its name and the requested version.
+///
+/// Returns no version for a package requested by name alone.
+pub fn split_pin(package: &str) -> (&str, Option<&str>) {
+    match package.rsplit_once('@') {
+        Some((name, version)) if !name.is_empty() && !version.is_empty() => (name, Some(version)),
+        _ => (package, None),
+    }
+}
+
+/// Parses a requested version, made of constraints separated by commas.
+pub fn parse_version_spec(text: &str) -> Result<VersionSpec, String> {
+    let constraints = text
+        .split(',')
+        .map(|c| {
+            let c = c.trim();
+            let constraint = if let Some(v) = c.strip_prefix(">=") {
+                Constraint::GreaterOrEqual(v.to_string())
+            } else if let Some(v) = c.strip_prefix("<=") {
+                Constraint::LessOrEqual(v.to_string())
+            } else if let Some(v) = c.strip_prefix('>') {
+                Constraint::Greater(v.to_string())
+            } else if let Some(v) = c.strip_prefix('<') {
+                Constraint::Less(v.to_string())
+            } else if let Some(v) = c.strip_suffix('*') {
+                Constraint::Prefix(v.to_string())
+            } else {
+                Constraint::Equal(c.trim_start_matches('=').to_string())
+            };
+
+            match &constraint {
+                Constraint::Prefix(_) => Ok(constraint),
+                Constraint::Equal(v)
+                | Constraint::Less(v)
+                | Constraint::LessOrEqual(v)
+                | Constraint::Greater(v)
+                | Constraint::GreaterOrEqual(v)
+                    if !v.trim().is_empty() =>
+                {
+                    Ok(constraint)
+                }
+                _ => Err(format!("The version '{}' is invalid.", text)),
+            }
+        })
+        .collect::<Result<Vec<Constraint>, String>>()?;
+
+    Ok(VersionSpec { constraints })
+}
+
+/// Compares two versions segment by segment, numbers numerically and letters alphabetically.
+///
+/// Separators like `.`, `-` and `+` only split segments, and a number is considered newer than
+/// letters, as with `rpm`. Trailing letters mark a pre-release, older than the version without.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (get_segments(a), get_segments(b));
+
+    for (x, y) in a.iter().zip(b.iter()) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    // A version with more segments is newer, unless they are letters of a pre-release like `rc1`
+    match (a.get(b.len()), b.get(a.len())) {
+        (Some(x), _) if x.parse::<u64>().is_err() => Ordering::Less,
+        (Some(_), _) => Ordering::Greater,
+        (_, Some(y)) if y.parse::<u64>().is_err() => Ordering::Greater,
+        (_, Some(_)) => Ordering::Less,
+        _ => Ordering::Equal,
+    }
+}
+
+/// Splits a version into runs of digits and runs of letters.
+fn get_segments(version: &str) -> Vec<&str> {
+    let mut segments = vec![];
+    let mut start = None;
+
+    for (i, c) in version.char_indices() {
+        match start {
+            Some(s) if !c.is_alphanumeric() => {
+                segments.push(&version[s..i]);
+                start = None;
+            }
+            Some(s)
+                if version[s..].starts_with(|p: char| p.is_ascii_digit()) != c.is_ascii_digit() =>
+            {
+                segments.push(&version[s..i]);
+                start = Some(i);
+            }
+            None if c.is_alphanumeric() => start = Some(i),
+            _ => (),
+        }
+    }
+    if let Some(s) = start {
+        segments.push(&version[s..]);
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_versions() -> Vec<String> {
+        ["1.9.2", "2.0.0-rc1", "1.10.0", "2.0.0", "1.2.3"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn split_pin_some_values() {
+        assert_eq!(
+            ("ripgrep", Some("13.0.0")),
+            split_pin("ripgrep@13.0.0"),
+            "Splits a package into its name and version"
+        );
+        assert_eq!(
+            ("ripgrep", None),
+            split_pin("ripgrep"),
+            "Gets no version for a package without one"
+        );
+        assert_eq!(
+            ("ripgrep@", None),
+            split_pin("ripgrep@"),
+            "Gets no version for a package with an empty one"
+        );
+    }
+
+    #[test]
+    fn compare_versions_some_values() {
+        assert_eq!(
+            Ordering::Less,
+            compare_versions("1.9.2", "1.10.0"),
+            "Compares numbers numerically"
+        );
+        assert_eq!(
+            Ordering::Greater,
+            compare_versions("2.0.0", "2.0.0-rc1"),
+            "Considers a version newer than one with more segments of letters"
+        );
+        assert_eq!(
+            Ordering::Equal,
+            compare_versions("29.1-2", "29.1.2"),
+            "Ignores the kind of separators"
+        );
+    }
+
+    #[test]
+    fn parse_version_spec_exact() {
+        assert_eq!(
+            Some("1.2.3"),
+            parse_version_spec("1.2.3").unwrap().get_exact(),
+            "Parses an exact version"
+        );
+        assert!(
+            parse_version_spec(">=1.2").unwrap().get_exact().is_none(),
+            "Parses a range as not exact"
+        );
+        assert!(
+            parse_version_spec(">=").is_err(),
+            "Rejects a constraint without a version"
+        );
+    }
+
+    #[test]
+    fn resolve_exact() {
+        let versions = ["13.0.0-2", "13.0.0-1", "1:13.0.1-1"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            Some("13.0.0-2"),
+            parse_version_spec("13.0.0-2").unwrap().resolve(&versions),
+            "Resolves a version along with its release"
+        );
+        assert_eq!(
+            Some("13.0.0-2"),
+            parse_version_spec("13.0.0").unwrap().resolve(&versions),
+            "Resolves the latest release of a version without its release"
+        );
+        assert_eq!(
+            Some("1:13.0.1-1"),
+            parse_version_spec("13.0.1").unwrap().resolve(&versions),
+            "Resolves a version without its epoch"
+        );
+        assert_eq!(
+            None,
+            parse_version_spec("13.0").unwrap().resolve(&versions),
+            "Resolves no version when only a part of one matches"
+        );
+    }
+
+    #[test]
+    fn resolve_range() {
+        assert_eq!(
+            Some("1.10.0"),
+            parse_version_spec(">=1.2,<2")
+                .unwrap()
+                .resolve(&get_versions()),
+            "Resolves the latest version within a range"
+        );
+        assert_eq!(
+            Some("1.9.2"),
+            parse_version_spec("1.9.*")
+                .unwrap()
+                .resolve(&get_versions()),
+            "Resolves the latest version with a prefix"
+        );
+        assert_eq!(
+            None,
+            parse_version_spec(">2.0.0")
+                .unwrap()
+                .resolve(&get_versions()),
+            "Resolves no version when none is within the range"
+        );
+    }
+}